@@ -0,0 +1,127 @@
+// Compares the single pass matcher day 1 uses against the find / rfind per pattern approach
+// it replaced, on generated input much larger than the real puzzle. Run with `cargo bench`
+use std::time::{Duration, Instant};
+
+use aoc23::implementations::day1::Day1;
+use aoc23::implementations::Solution;
+use aoc23::utility::generic_error::GenericResult;
+use aoc23::utility::timing::{format_duration, TimingSummary};
+
+const LINES: usize = 200_000;
+const REPEATS: usize = 10;
+
+const WORDS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+// Small linear congruential generator so every run benchmarks the same input
+struct Generator(u64);
+
+impl Generator {
+    fn next(&mut self, below: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % below
+    }
+}
+
+// Lines of filler letters with spelled and numeric digits mixed in, every line has at least one
+fn generate_input() -> String {
+    let mut generator = Generator(2023);
+    let mut lines = Vec::with_capacity(LINES);
+    for _ in 0..LINES {
+        let mut line = String::new();
+        for index in 0..(2 + generator.next(8)) {
+            match generator.next(3) {
+                0 if index > 0 => line.push((b'a' + generator.next(26) as u8) as char),
+                1 => line.push((b'1' + generator.next(9) as u8) as char),
+                _ => line.push_str(WORDS[1 + generator.next(9)]),
+            }
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+// The previous implementation, kept here as the baseline
+fn find_value<Find, Compare>(line: &str, find_lambda: Find, compare_lambda: Compare) -> Option<usize>
+where
+    Find: Fn(&str, &str) -> Option<usize>,
+    Compare: Fn(usize, usize) -> bool,
+{
+    let string_value_pairs = [
+        ("0", 0),
+        ("one", 1),
+        ("1", 1),
+        ("two", 2),
+        ("2", 2),
+        ("three", 3),
+        ("3", 3),
+        ("four", 4),
+        ("4", 4),
+        ("five", 5),
+        ("5", 5),
+        ("six", 6),
+        ("6", 6),
+        ("seven", 7),
+        ("7", 7),
+        ("eight", 8),
+        ("8", 8),
+        ("nine", 9),
+        ("9", 9),
+    ];
+
+    let mut current_match: Option<(usize, u8)> = None;
+
+    for string_value_pair in string_value_pairs {
+        let found_string = find_lambda(line, string_value_pair.0);
+        if let Some(index) = found_string {
+            if current_match.is_none() || compare_lambda(index, current_match.unwrap().0) {
+                current_match = Some((index, string_value_pair.1));
+            }
+        }
+    }
+
+    current_match.map(|(_, value)| value as usize)
+}
+
+fn baseline_part_2(input: &str) -> usize {
+    let mut result = 0;
+    for line in input.lines() {
+        let first = find_value(line, |line, pattern| line.find(pattern), |left, right| left < right);
+        let last = find_value(line, |line, pattern| line.rfind(pattern), |left, right| left > right);
+        if let (Some(tens), Some(units)) = (first, last) {
+            result += 10 * tens + units;
+        }
+    }
+    result
+}
+
+fn bench(name: &str, f: impl Fn() -> GenericResult<usize>) -> GenericResult<usize> {
+    let mut times: Vec<Duration> = vec![];
+    let mut answer = 0;
+    for _ in 0..REPEATS {
+        let start = Instant::now();
+        answer = f()?;
+        times.push(start.elapsed());
+    }
+
+    if let Some(summary) = TimingSummary::new(&times) {
+        println!(
+            "{:<10} {} / {} / {}",
+            name,
+            format_duration(summary.min),
+            format_duration(summary.median),
+            format_duration(summary.max)
+        );
+    }
+    Ok(answer)
+}
+
+fn main() -> GenericResult<()> {
+    let input = generate_input();
+    println!("day 1 part 2 on {} generated lines, min / median / max over {} runs", LINES, REPEATS);
+
+    let baseline = bench("find/rfind", || Ok(baseline_part_2(&input)))?;
+    // Parsing only copies the input and builds the matchers, so it is timed along with the solve
+    let matcher = bench("matcher", || Day1::part_2(&Day1::parse(&input)?))?;
+    assert_eq!(baseline, matcher, "the two implementations disagree");
+    Ok(())
+}
@@ -1,129 +1,411 @@
-use std::io::{BufRead, BufReader};
+use std::str::FromStr;
 
-fn part_1(input_path : &String) -> std::io::Result<()> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
+use crate::implementations::{ExplainFormat, Part, Solution, Warning};
+use crate::utility::config::DayConfig;
+use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::utility::input::{lines, read_input};
+use crate::utility::matcher::{Match, Matcher};
+use crate::utility::toml_file::{error_at_key, parse_table};
 
-    let mut result = 0;
+pub struct Day1;
 
-    for line in reader.lines() {
-        let line = line?;
+// Words for 0 to 9 in order, an empty string means the language has no word for that digit
+const ENGLISH: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const FRENCH: [&str; 10] = ["zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf"];
+const GERMAN: [&str; 10] = ["null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun"];
+const ROMAN: [&str; 10] = ["", "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"];
 
-        let first = line.find(|c: char| (c >= '0') && (c <= '9'));
-        let last = line.rfind(|c: char| (c >= '0') && (c <= '9'));
+pub const BUILTIN_VOCABULARIES: [&str; 5] = ["numerals", "english", "french", "german", "roman"];
 
-        let offset = '0' as u8;
+// The words that count as digits. Every vocabulary also accepts the numerals 0 to 9
+#[derive(Clone, PartialEq, Debug)]
+pub struct Vocabulary {
+    pub words: Vec<(String, usize)>,
+}
+
+impl Vocabulary {
+    pub fn numerals() -> Vocabulary {
+        Vocabulary {
+            words: (0..10).map(|digit| (digit.to_string(), digit)).collect(),
+        }
+    }
 
-        let value = match (first, last) {
-            (Some(tens), Some(units)) => {
-                10 * (line.as_bytes()[tens] - offset) + (line.as_bytes()[units] - offset)
+    fn spelled(words: &[&str; 10]) -> Vocabulary {
+        let mut vocabulary = Vocabulary::numerals();
+        for (digit, word) in words.iter().enumerate() {
+            if !word.is_empty() {
+                vocabulary.words.push((word.to_string(), digit));
             }
-            _ => {
-                println!("No digits found in line {}", line);
-                0
+        }
+        vocabulary
+    }
+
+    pub fn builtin(name: &str) -> Option<Vocabulary> {
+        match name {
+            "numerals" => Some(Vocabulary::numerals()),
+            "english" => Some(Vocabulary::spelled(&ENGLISH)),
+            "french" => Some(Vocabulary::spelled(&FRENCH)),
+            "german" => Some(Vocabulary::spelled(&GERMAN)),
+            "roman" => Some(Vocabulary::spelled(&ROMAN)),
+            _ => None,
+        }
+    }
+
+    // A TOML table of `word = digit`, e.g. `uno = 1`
+    pub fn parse(contents: &str) -> GenericResult<Vocabulary> {
+        let table = parse_table(contents, "day1::Vocabulary")?;
+
+        let mut vocabulary = Vocabulary::numerals();
+        for (word, value) in table.iter() {
+            match value.as_integer() {
+                Some(digit @ 0..=9) => vocabulary.words.push((word.clone(), digit as usize)),
+                _ => {
+                    let error = GenericError::BasicError(format!("\"{}\" should be a digit from 0 to 9, not {}", word, value));
+                    return Err(error_at_key(error, "day1::Vocabulary", contents, &[word]));
+                }
             }
-        };
+        }
+        Ok(vocabulary)
+    }
 
-        //println!("Line: {} - Value: {}", line, value);
-        result += value as usize;
+    // Either the name of a built in vocabulary or the path of a vocabulary file
+    pub fn load(name_or_path: &str) -> GenericResult<Vocabulary> {
+        if let Some(vocabulary) = Vocabulary::builtin(name_or_path) {
+            return Ok(vocabulary);
+        }
+        read_input(name_or_path)
+            .and_then(|contents| Vocabulary::parse(&contents))
+            .map_err(|e| e.with_path(name_or_path))
     }
 
-    println!("Part one result: {}", result);
+    pub fn matcher(&self) -> Matcher<usize> {
+        Matcher::new(self.words.iter().map(|(word, digit)| (word, *digit)))
+    }
+}
 
-    Ok(())
+// What to do with lines that have no digits: strict fails the part, lenient leaves them out of
+// the total and reports them as warnings
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Policy {
+    Strict,
+    Lenient,
 }
 
-fn find_value<Find, Compare>(
-    line: &String,
-    find_lambda: Find,
-    compare_lambda: Compare,
-) -> Option<usize>
-where
-    Find: Fn(&String, &str) -> Option<usize>,
-    Compare: Fn(usize, usize) -> bool,
-{
-    let string_value_pairs = [
-        ("0", 0),
-        ("one", 1),
-        ("1", 1),
-        ("two", 2),
-        ("2", 2),
-        ("three", 3),
-        ("3", 3),
-        ("four", 4),
-        ("4", 4),
-        ("five", 5),
-        ("5", 5),
-        ("six", 6),
-        ("6", 6),
-        ("seven", 7),
-        ("7", 7),
-        ("eight", 8),
-        ("8", 8),
-        ("nine", 9),
-        ("9", 9),
-    ];
-
-    let mut current_match: Option<(usize, u8)> = None;
-
-    for string_value_pair in string_value_pairs {
-        let found_string = find_lambda(&line, string_value_pair.0);
-        match found_string {
-            Some(index) => {
-                if current_match == None || compare_lambda(index, current_match.unwrap().0) {
-                    current_match = Some((index, string_value_pair.1));
-                }
-            }
-            None => (),
-        };
+impl FromStr for Policy {
+    type Err = GenericError;
+
+    fn from_str(s: &str) -> GenericResult<Policy> {
+        match s {
+            "strict" => Ok(Policy::Strict),
+            "lenient" => Ok(Policy::Lenient),
+            _ => Err(GenericError::UsageError(format!("policy should be strict or lenient, not \"{}\"", s))),
+        }
     }
+}
 
-    // Unwrap and return
-    match current_match {
-        Some((_, value)) => Some(value as usize),
-        None => None,
+// The calibration document together with the vocabulary each part reads it with
+pub struct Calibration {
+    pub document: String,
+    pub matchers: [Matcher<usize>; 2],
+    pub policy: Policy,
+}
+
+impl Calibration {
+    pub fn new(document: &str, part_1: &Vocabulary, part_2: &Vocabulary, policy: Policy) -> Calibration {
+        Calibration {
+            document: document.to_string(),
+            matchers: [part_1.matcher(), part_2.matcher()],
+            policy,
+        }
     }
 }
 
-fn part_2(input_path : &String) -> std::io::Result<()> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
+// Line numbers count from 1, like an editor
+fn lines_without_digits<'a>(document: &'a str, matcher: &Matcher<usize>) -> Vec<(usize, &'a str)> {
+    lines(document)
+        .enumerate()
+        .filter(|(_, line)| matcher.first_and_last(line).is_none())
+        .map(|(index, line)| (index + 1, line))
+        .collect()
+}
 
-    let mut result = 0;
+// Located at the first offending line, with every other one listed in the message
+fn no_digits(document: &str, missing: &[(usize, &str)]) -> GenericError {
+    let numbers: Vec<String> = missing.iter().map(|(number, _)| number.to_string()).collect();
+    let message = match missing.len() {
+        1 => format!("no digits found on line {}", numbers[0]),
+        _ => format!("no digits found on lines {}", numbers.join(", ")),
+    };
+    GenericError::BasicError(message).parsing("day1::calibration_value", document, missing[0].1)
+}
+
+fn value_of(first: &Match<usize>, last: &Match<usize>) -> usize {
+    10 * first.value + last.value
+}
+
+// One scan per line finds the first and last digit, overlapping words like "eightwo" count
+// as both of their digits
+fn calibration_total(document: &str, matcher: &Matcher<usize>, policy: Policy) -> GenericResult<usize> {
+    if policy == Policy::Strict {
+        let missing = lines_without_digits(document, matcher);
+        if !missing.is_empty() {
+            return Err(no_digits(document, &missing));
+        }
+    }
+
+    Ok(lines(document)
+        .filter_map(|line| matcher.first_and_last(line))
+        .map(|(first, last)| value_of(&first, &last))
+        .sum())
+}
+
+// A matched word or numeral, start and end are byte offsets into the line
+#[derive(PartialEq, Eq, Debug, serde::Serialize)]
+pub struct Token {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+    pub digit: usize,
+}
+
+impl Token {
+    fn new(line: &str, found: &Match<usize>) -> Token {
+        Token {
+            text: line[found.start..found.end].to_string(),
+            start: found.start,
+            end: found.end,
+            digit: *found.value,
+        }
+    }
+}
+
+// How one line was read, first, last and value are missing when the line has no digits
+#[derive(PartialEq, Eq, Debug, serde::Serialize)]
+pub struct LineExplanation {
+    pub line: usize,
+    pub text: String,
+    pub first: Option<Token>,
+    pub last: Option<Token>,
+    pub value: Option<usize>,
+}
+
+#[derive(PartialEq, Eq, Debug, serde::Serialize)]
+pub struct Explanation {
+    pub part: usize,
+    pub lines: Vec<LineExplanation>,
+    pub total: usize,
+}
+
+pub fn explain_calibration(calibration: &Calibration, part: Part) -> Explanation {
+    let matcher = &calibration.matchers[part.number() - 1];
+    let lines: Vec<LineExplanation> = lines(&calibration.document)
+        .enumerate()
+        .map(|(index, line)| {
+            let found = matcher.first_and_last(line);
+            LineExplanation {
+                line: index + 1,
+                text: line.to_string(),
+                first: found.map(|(first, _)| Token::new(line, &first)),
+                last: found.map(|(_, last)| Token::new(line, &last)),
+                value: found.map(|(first, last)| value_of(&first, &last)),
+            }
+        })
+        .collect();
+
+    Explanation {
+        part: part.number(),
+        total: lines.iter().filter_map(|line| line.value).sum(),
+        lines,
+    }
+}
 
-    for line in reader.lines() {
-        let line = line?;
+fn describe(token: &Token) -> String {
+    format!("\"{}\" at {}..{}", token.text, token.start, token.end)
+}
 
-        let first = find_value(
-            &line,
-            |line: &String, pattern: &str| line.find(pattern),
-            |left: usize, right: usize| left < right,
-        );
-        let last = find_value(
-            &line,
-            |line: &String, pattern: &str| line.rfind(pattern),
-            |left: usize, right: usize| left > right,
-        );
+// For the explain subcommand, the document is read with the same vocabularies as when solving
+pub fn explain(input: &str, part: Part, config: &DayConfig, format: ExplainFormat) -> GenericResult<String> {
+    let explanation = explain_calibration(&Day1::parse_configured(input, config)?, part);
+    if format == ExplainFormat::Json {
+        return Ok(serde_json::to_string_pretty(&explanation)? + "\n");
+    }
 
-        let value = match (first, last) {
-            (Some(tens), Some(units)) => 10 * tens + units,
-            _ => {
-                println!("No digits found in line {}", line);
-                0
+    let width = explanation.lines.len().to_string().len();
+    let mut output = String::new();
+    for line in explanation.lines.iter() {
+        let reading = match (&line.first, &line.last, line.value) {
+            (Some(first), Some(last), Some(value)) => {
+                format!("{}, {} -> {}", describe(first), describe(last), value)
             }
+            _ => String::from("no digits"),
         };
+        output += &format!("{:>width$} | {} | {}\n", line.line, line.text, reading);
+    }
+    output += &format!("Part {} total: {}\n", part.name(), explanation.total);
+    Ok(output)
+}
+
+impl Solution for Day1 {
+    type Parsed = Calibration;
+    type Answer = usize;
+
+    fn parse(input: &str) -> GenericResult<Calibration> {
+        Self::parse_configured(input, &DayConfig::default())
+    }
+
+    // part_1_vocabulary and part_2_vocabulary pick what each part reads as a digit, policy
+    // picks what happens to lines without any
+    fn parse_configured(input: &str, config: &DayConfig) -> GenericResult<Calibration> {
+        let part_1 = Vocabulary::load(config.get("part_1_vocabulary").unwrap_or("numerals"))?;
+        let part_2 = Vocabulary::load(config.get("part_2_vocabulary").unwrap_or("english"))?;
+        let policy = config.get("policy").unwrap_or("strict").parse()?;
+        Ok(Calibration::new(input, &part_1, &part_2, policy))
+    }
+
+    fn part_1(calibration: &Calibration) -> GenericResult<usize> {
+        calibration_total(&calibration.document, &calibration.matchers[0], calibration.policy)
+    }
 
-        //println!("Line: {} - Value: {}", line, value);
-        result += value as usize;
+    fn part_2(calibration: &Calibration) -> GenericResult<usize> {
+        calibration_total(&calibration.document, &calibration.matchers[1], calibration.policy)
     }
 
-    println!("Part two result: {}", result);
+    fn warnings(calibration: &Calibration, part: Part) -> Vec<Warning> {
+        if calibration.policy == Policy::Strict {
+            return vec![];
+        }
+        let matcher = &calibration.matchers[part.number() - 1];
+        lines_without_digits(&calibration.document, matcher)
+            .into_iter()
+            .map(|(number, _)| Warning {
+                line: Some(number),
+                message: String::from("no digits found, line skipped"),
+            })
+            .collect()
+    }
+}
+
+#[test]
+pub fn run_test_1() -> GenericResult<()> {
+    assert_eq!(Day1::part_1(&Day1::parse_file("test_data/day1/example.txt")?)?, 142);
+    Ok(())
+}
+
+#[test]
+pub fn run_test_2() -> GenericResult<()> {
+    assert_eq!(Day1::part_2(&Day1::parse_file("test_data/day1/example_part_2.txt")?)?, 281);
+    Ok(())
+}
+
+#[test]
+pub fn test_lines_without_digits_are_errors() -> GenericResult<()> {
+    let calibration = Day1::parse("1abc2\nnothing here\n")?;
+    assert!(Day1::part_1(&calibration).is_err());
+    assert!(Day1::part_2(&calibration).is_err());
+    Ok(())
+}
 
+#[test]
+pub fn test_overlapping_words_count_as_both_digits() -> GenericResult<()> {
+    assert_eq!(Day1::part_2(&Day1::parse("eightwo")?)?, 82);
+    assert_eq!(Day1::part_2(&Day1::parse("xtwone3fourtwone")?)?, 21);
+    assert_eq!(Day1::part_2(&Day1::parse("7")?)?, 77);
     Ok(())
 }
 
-pub fn run(input_path : &String) -> std::io::Result<()> {
-    part_1(&input_path)?;
-    part_2(&input_path)?;
+#[test]
+pub fn test_builtin_vocabularies() -> GenericResult<()> {
+    let total = |vocabulary: &str, document: &str| {
+        calibration_total(document, &Vocabulary::builtin(vocabulary).unwrap().matcher(), Policy::Strict)
+    };
+    assert_eq!(total("english", "zeroxone")?, 1);
+    assert_eq!(total("french", "huitroisept")?, 87);
+    assert_eq!(total("german", "fünfundzwanzig")?, 55);
+    assert_eq!(total("german", "dreiundzwanzigzwei")?, 32);
+    assert_eq!(total("roman", "VIII and IX")?, 89);
+    assert!(total("numerals", "one").is_err());
+    assert!(BUILTIN_VOCABULARIES.iter().all(|name| Vocabulary::builtin(name).is_some()));
     Ok(())
 }
+
+#[test]
+pub fn test_vocabulary_files() -> GenericResult<()> {
+    let vocabulary = Vocabulary::parse("uno = 1\ndos = 2\n\"tres\" = 3")?;
+    assert_eq!(calibration_total("unodos5tres", &vocabulary.matcher(), Policy::Strict)?, 13);
+    assert!(Vocabulary::parse("uno = 1\ndiez = 10").is_err());
+    assert!(Vocabulary::parse("uno = \"one\"").is_err());
+    assert!(Vocabulary::parse("uno =").is_err());
+    Ok(())
+}
+
+#[test]
+pub fn test_parts_are_configured_vocabularies() -> GenericResult<()> {
+    let mut config = DayConfig::default();
+    config.set("part_1_vocabulary", "english");
+    config.set("part_2_vocabulary", "roman");
+    let calibration = Day1::parse_configured("oneVtwo", &config)?;
+    assert_eq!(Day1::part_1(&calibration)?, 12);
+    assert_eq!(Day1::part_2(&calibration)?, 55);
+
+    config.set("part_2_vocabulary", "test_data/day1/missing_vocabulary.toml");
+    assert!(Day1::parse_configured("1", &config).is_err());
+    Ok(())
+}
+
+#[test]
+pub fn test_strict_policy_lists_every_line_without_digits() -> GenericResult<()> {
+    let calibration = Day1::parse("1abc2\nnothing\n3\nnone here either")?;
+    let error = Day1::part_1(&calibration).unwrap_err();
+    assert!(error.to_string().contains("no digits found on lines 2, 4"), "{}", error);
+    assert!(Day1::warnings(&calibration, Part::One).is_empty());
+    Ok(())
+}
+
+#[test]
+pub fn test_lenient_policy_skips_lines_with_warnings() -> GenericResult<()> {
+    let mut config = DayConfig::default();
+    config.set("policy", "lenient");
+    let calibration = Day1::parse_configured("1abc2\nnothing\nthree", &config)?;
+    assert_eq!(Day1::part_1(&calibration)?, 12);
+    assert_eq!(Day1::part_2(&calibration)?, 45);
+
+    let warnings = Day1::warnings(&calibration, Part::One);
+    assert_eq!(warnings.iter().map(|warning| warning.line).collect::<Vec<_>>(), vec![Some(2), Some(3)]);
+    assert_eq!(Day1::warnings(&calibration, Part::Two).len(), 1);
+
+    config.set("policy", "sloppy");
+    assert!(Day1::parse_configured("1", &config).is_err());
+    Ok(())
+}
+
+#[test]
+pub fn test_explanations_show_the_chosen_tokens() -> GenericResult<()> {
+    let explanation = explain_calibration(&Day1::parse("xtwone3four\nnothing")?, Part::Two);
+    assert_eq!(explanation.total, 24);
+    let line = &explanation.lines[0];
+    assert_eq!(
+        line.first,
+        Some(Token {
+            text: String::from("two"),
+            start: 1,
+            end: 4,
+            digit: 2
+        })
+    );
+    assert_eq!(line.last.as_ref().map(|token| (token.start, token.end, token.digit)), Some((7, 11, 4)));
+    assert_eq!(line.value, Some(24));
+    assert_eq!((explanation.lines[1].line, explanation.lines[1].value), (2, None));
+
+    let text = explain("xtwone3four", Part::Two, &DayConfig::default(), ExplainFormat::Text)?;
+    assert!(text.starts_with("1 | xtwone3four | \"two\" at 1..4, \"four\" at 7..11 -> 24\n"), "{}", text);
+    let json: serde_json::Value = serde_json::from_str(&explain("7", Part::One, &DayConfig::default(), ExplainFormat::Json)?)?;
+    assert_eq!(json["lines"][0]["first"]["digit"], 7);
+    assert_eq!(json["total"], 77);
+    Ok(())
+}
+
+#[test]
+pub fn test_vocabulary_errors_point_at_the_word() {
+    let error = Vocabulary::parse("unos = 1\nuno = 10").unwrap_err();
+    assert!(error.render().contains("--> <input>:2:1"), "{}", error.render());
+}
@@ -1,119 +1,265 @@
-use std::io::{BufRead, BufReader};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
-struct Draw {
-    r: usize,
-    g: usize,
-    b: usize,
+use crate::implementations::Solution;
+use crate::utility::config::DayConfig;
+use crate::utility::generic_error::{GenericError, GenericResult, ParseContext};
+use crate::utility::input::lines;
+
+pub struct Day2;
+
+// How many cubes of each colour were shown, colours that weren't shown are absent
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Draw {
+    pub counts: BTreeMap<String, usize>,
 }
 
 impl Draw {
-    fn power(&self) -> usize {
-        self.r * self.g * self.b
+    pub fn new<'a>(counts: impl IntoIterator<Item = (&'a str, usize)>) -> Draw {
+        let mut draw = Draw::default();
+        for (colour, count) in counts {
+            *draw.counts.entry(colour.to_string()).or_insert(0) += count;
+        }
+        draw
+    }
+
+    pub fn count(&self, colour: &str) -> usize {
+        self.counts.get(colour).copied().unwrap_or(0)
+    }
+}
+
+// The most cubes of each colour the bag holds
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Bag {
+    pub limits: BTreeMap<String, usize>,
+}
+
+impl Default for Bag {
+    // The bag from the puzzle
+    fn default() -> Bag {
+        Bag {
+            limits: [("red", 12), ("green", 13), ("blue", 14)]
+                .into_iter()
+                .map(|(colour, limit)| (colour.to_string(), limit))
+                .collect(),
+        }
+    }
+}
+
+impl Bag {
+    // `bag.<colour>` settings describe the whole bag, e.g. bag.yellow=5 and bag.purple=3 is a bag
+    // of only yellow and purple cubes. Without any the puzzle's bag is used
+    pub fn configured(config: &DayConfig) -> GenericResult<Bag> {
+        let mut limits = BTreeMap::new();
+        for (colour, limit) in config.section("bag") {
+            let limit = limit.parse::<usize>().map_err(|_| {
+                GenericError::UsageError(format!("bag.{} should be a number of cubes, not \"{}\"", colour, limit))
+            })?;
+            limits.insert(colour.to_string(), limit);
+        }
+        if limits.is_empty() {
+            return Ok(Bag::default());
+        }
+        Ok(Bag { limits })
+    }
+
+    // A colour the bag doesn't hold can't be drawn at all
+    pub fn allows(&self, draw: &Draw) -> bool {
+        draw.counts
+            .iter()
+            .all(|(colour, count)| *count <= self.limits.get(colour).copied().unwrap_or(0))
     }
 }
 
 impl FromStr for Draw {
-    type Err = Box<dyn std::error::Error>;
+    type Err = GenericError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let split: Vec<&str> = input.split(',').collect();
 
-        let mut r = 0;
-        let mut g = 0;
-        let mut b = 0;
+        let mut draw = Draw::default();
 
         for drawn_colour in split {
-            let parsed = sscanf::sscanf!(drawn_colour.trim(), "{} {}", usize, str);
+            let drawn_colour = drawn_colour.trim();
+            let (count, colour) =
+                sscanf::sscanf!(drawn_colour, "{} {}", usize, str).parsing("day2::Draw", input, drawn_colour)?;
 
-            match parsed {
-                Ok((count, "red")) => r += count,
-                Ok((count, "green")) => g += count,
-                Ok((count, "blue")) => b += count,
-                _ => return Err(format!("Failed to parse {}", drawn_colour))?,
+            if !colour.chars().all(char::is_alphabetic) {
+                let error = GenericError::BasicError(format!("\"{}\" is not a colour", colour));
+                return Err(error.parsing("day2::Draw", input, colour));
             }
+            *draw.counts.entry(colour.to_string()).or_insert(0) += count;
         }
 
-        Ok(Draw { r, g, b })
+        Ok(draw)
+    }
+}
+
+pub struct Game {
+    pub id: usize,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    // The fewest cubes of each colour that could have been in the bag, colours in the bag that
+    // this game never showed need none
+    pub fn minimum_bag(&self, bag: &Bag) -> Bag {
+        let mut limits: BTreeMap<String, usize> = bag.limits.keys().map(|colour| (colour.clone(), 0)).collect();
+        for draw in self.draws.iter() {
+            for (colour, count) in draw.counts.iter() {
+                let limit = limits.entry(colour.clone()).or_insert(0);
+                *limit = std::cmp::max(*limit, *count);
+            }
+        }
+        Bag { limits }
     }
 }
 
-fn is_valid_draw_for_part_1(draw : &Draw) -> bool {
-    draw.r <= 12 && draw.g <= 13 && draw.b <= 14
+// The games together with the bag they are checked against
+pub struct Games {
+    pub games: Vec<Game>,
+    pub bag: Bag,
 }
 
-fn part_1(input_path: &str) -> std::io::Result<usize> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
+// Splits a "Game N: draw; draw; ..." line into the game id and its draws
+fn parse_game(input: &str, line: &str) -> GenericResult<Game> {
+    let (game_str, draws_str) = line.split_once(':').ok_or_else(|| {
+        GenericError::BasicError(String::from("expected ':' after the game id")).parsing("day2::Game", input, line)
+    })?;
+    let game_id = sscanf::sscanf!(game_str, "Game {}", usize).parsing("day2::Game", input, game_str)?;
+
+    let draws = draws_str
+        .split(';')
+        .map(|draw_string| Draw::from_str(draw_string).parsing("day2::Game", input, draw_string))
+        .collect::<GenericResult<Vec<Draw>>>()?;
+
+    Ok(Game { id: game_id, draws })
+}
 
+fn part_1(games: &[Game], bag: &Bag) -> usize {
     let mut result = 0;
 
-    for line in reader.lines() {
-        let unwrapped_line = line?;
-        let split_line: Vec<&str> = unwrapped_line.split(&[':', ';']).collect();
-        let game_id = sscanf::sscanf!(split_line[0], "Game {}", usize).unwrap();
-        
-        let valid_draws_count = (&split_line[1..])
-            .into_iter()
-            .map(|draw_string| Draw::from_str(draw_string).unwrap())
-            .filter(|draw| is_valid_draw_for_part_1(draw))
+    for game in games {
+        let all_draws = &game.draws;
+
+        let valid_draws_count = all_draws
+            .iter()
+            .filter(|draw| bag.allows(draw))
             .count();
-        
-        if valid_draws_count == (split_line.len() - 1) {
-            //println!("Valid: {}", unwrapped_line);
-            result += game_id;
+
+        if valid_draws_count == all_draws.len() {
+            //println!("Valid: {}", game.id);
+            result += game.id;
         }
         else {
-            //println!("Invalid: {}", unwrapped_line);
+            //println!("Invalid: {}", game.id);
         }
     }
 
-    Ok(result)
+    result
 }
 
-fn part_2(input_path: &str) -> std::io::Result<usize> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
-
+// The power of a game is the product of its minimum bag over every colour, so a colour in the
+// bag that the game never showed makes its power 0
+fn part_2(games: &[Game], bag: &Bag) -> usize {
     let mut result = 0;
 
-    for line in reader.lines() {
-        let unwrapped_line = line?;
-        let split_line: Vec<&str> = unwrapped_line.split(&[':', ';']).collect();
-        
-        let all_draws : Vec<Draw> = (&split_line[1..])
-            .into_iter()
-            .map(|draw_string| Draw::from_str(draw_string).unwrap())
-            .collect();
-
-        let mut componentwise_max_draw = Draw { r:0, g:0, b:0 };
-
-        for draw in all_draws { 
-            componentwise_max_draw.r = std::cmp::max(componentwise_max_draw.r, draw.r);
-            componentwise_max_draw.g = std::cmp::max(componentwise_max_draw.g, draw.g);
-            componentwise_max_draw.b = std::cmp::max(componentwise_max_draw.b, draw.b);
-        }
-        
-        result += componentwise_max_draw.power();
+    for game in games {
+        result += game.minimum_bag(bag).limits.values().product::<usize>();
+    }
+
+    result
+}
+
+impl Solution for Day2 {
+    type Parsed = Games;
+    type Answer = usize;
+
+    fn parse(input: &str) -> GenericResult<Games> {
+        Self::parse_configured(input, &DayConfig::default())
+    }
+
+    fn parse_configured(input: &str, config: &DayConfig) -> GenericResult<Games> {
+        Ok(Games {
+            games: lines(input).map(|line| parse_game(input, line)).collect::<GenericResult<Vec<Game>>>()?,
+            bag: Bag::configured(config)?,
+        })
     }
 
-    Ok(result)
+    fn part_1(games: &Games) -> GenericResult<usize> {
+        Ok(part_1(&games.games, &games.bag))
+    }
+
+    fn part_2(games: &Games) -> GenericResult<usize> {
+        Ok(part_2(&games.games, &games.bag))
+    }
 }
 
 #[test]
-pub fn run_test_1() -> std::io::Result<()> {
-    assert_eq!(part_1("test_data/day2/example.txt").unwrap(), 8);
+pub fn run_test_1() -> GenericResult<()> {
+    assert_eq!(Day2::part_1(&Day2::parse_file("test_data/day2/example.txt")?)?, 8);
     Ok(())
 }
 
 #[test]
-pub fn run_test_2() -> std::io::Result<()> {
-    assert_eq!(part_2("test_data/day2/example.txt").unwrap(), 2286);
+pub fn run_test_2() -> GenericResult<()> {
+    assert_eq!(Day2::part_2(&Day2::parse_file("test_data/day2/example.txt")?)?, 2286);
     Ok(())
 }
 
-pub fn run(input_path: &String) -> std::io::Result<()> {
-    println!("Part one result: {}", part_1(&input_path)?);
-    println!("Part two result: {}", part_2(&input_path)?);
+#[test]
+pub fn test_malformed_games_are_errors() {
+    assert!(Day2::parse("Game 1: 3 blue, 4 2").is_err());
+    assert!(Day2::parse("Game 1: 3 blue, yellow").is_err());
+    assert!(Day2::parse("Game x: 3 blue").is_err());
+    assert!(Day2::parse("Game 1 3 blue").is_err());
+}
+
+#[test]
+pub fn test_games_can_be_built_directly() {
+    let games = vec![
+        Game { id: 1, draws: vec![Draw::new([("red", 12), ("green", 13), ("blue", 14)])] },
+        Game { id: 2, draws: vec![Draw::new([("red", 1), ("green", 2), ("blue", 3)]), Draw::new([("red", 13)])] },
+    ];
+    assert_eq!(part_1(&games, &Bag::default()), 1);
+    assert_eq!(part_2(&games, &Bag::default()), 12 * 13 * 14 + 13 * 2 * 3);
+    let unshown_green = vec![Game { id: 1, draws: vec![Draw::new([("blue", 3), ("red", 4)])] }];
+    assert_eq!(part_2(&unshown_green, &Bag::default()), 0);
+}
+
+#[test]
+pub fn test_other_colours_and_bags() -> GenericResult<()> {
+    let input = "Game 1: 3 blue, 4 yellow, 2 green; 2 yellow, 1 red\nGame 2: 1 red, 2 green, 3 blue";
+    let games = Day2::parse(input)?;
+    assert_eq!(games.games[0].draws[0].count("yellow"), 4);
+    // The puzzle's bag has no yellow cubes
+    assert_eq!(Day2::part_1(&games)?, 2);
+    assert_eq!(Day2::part_2(&games)?, 24 + 6);
+
+    // A configured bag replaces the puzzle's, so this one holds no red or green cubes
+    let mut config = DayConfig::default();
+    config.set("bag.yellow", "4");
+    config.set("bag.blue", "3");
+    assert_eq!(Day2::part_1(&Day2::parse_configured(input, &config)?)?, 0);
+    config.set("bag.red", "1");
+    config.set("bag.green", "2");
+    assert_eq!(Day2::part_1(&Day2::parse_configured(input, &config)?)?, 3);
+    // Game 2 showed no yellow cubes, so its power is now 0
+    assert_eq!(Day2::part_2(&Day2::parse_configured(input, &config)?)?, 24);
+
+    config.set("bag.yellow", "lots");
+    assert!(Day2::parse_configured(input, &config).is_err());
+    Ok(())
+}
+
+#[test]
+pub fn test_bags_without_red_green_or_blue() -> GenericResult<()> {
+    let mut config = DayConfig::default();
+    config.set("bag.yellow", "5");
+    config.set("bag.purple", "3");
+    let games = Day2::parse_configured("Game 1: 3 yellow, 4 purple\nGame 2: 1 yellow\nGame 3: 2 purple, 1 red", &config)?;
+    assert_eq!(Day2::part_1(&games)?, 2);
+    // Only game 1 showed both colours in the bag
+    assert_eq!(Day2::part_2(&games)?, 12);
     Ok(())
 }
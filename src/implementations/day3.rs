@@ -1,220 +1,566 @@
-use std::io::{BufRead, BufReader};
 use std::str::FromStr;
 
+use crate::implementations::{RenderFormat, Solution};
+use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::utility::grid::{Grid, Position};
+use crate::utility::input::lines;
+
+pub struct Day3;
+
+// A run of digits on a single row, covering columns start_col..=end_col
 #[derive(PartialEq, Debug, Clone)]
-struct Number {
-    value: u32,
-    start_index: i64,
-    end_index: i64,
+pub struct Number {
+    pub value: u32,
+    pub row: usize,
+    pub start_col: usize,
+    pub end_col: usize,
+}
+
+// Where a number sits, two numbers with the same value are still different numbers
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct NumberId {
+    pub row: usize,
+    pub start_col: usize,
+    pub end_col: usize,
 }
 
 impl Number {
-    fn new(value: u32, start_index: i64, end_index: i64) -> Number {
-        Number {
-            value: value,
-            start_index: start_index,
-            end_index: end_index,
+    pub fn id(&self) -> NumberId {
+        NumberId {
+            row: self.row,
+            start_col: self.start_col,
+            end_col: self.end_col,
         }
     }
 
-    fn is_index_adjacent(&self, index: i64) -> bool {
-        index >= (self.start_index - 1) && index <= (self.end_index + 1)
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        (self.start_col..=self.end_col).map(|col| (self.row, col))
     }
 }
 
 #[derive(PartialEq, Debug)]
-struct Symbol {
-    value: char,
-    index: i64,
+pub struct Symbol {
+    pub value: char,
+    pub position: Position,
+}
+
+pub fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+// Which symbols a query looks at
+#[derive(Clone, Debug)]
+pub enum SymbolSet {
+    Any,
+    Only(Vec<char>),
 }
 
-impl Symbol {
-    fn new(value: char, index: i64) -> Symbol {
-        Symbol {
-            value: value,
-            index: index,
+impl SymbolSet {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            SymbolSet::Any => is_symbol(c),
+            SymbolSet::Only(symbols) => symbols.contains(&c),
         }
     }
 }
 
-#[derive(Default, PartialEq, Debug)]
-struct Row {
-    numbers: Vec<Number>,
-    symbols: Vec<Symbol>,
+// How many distinct numbers a symbol needs next to it
+#[derive(Clone, Copy, Debug)]
+pub enum NumberCount {
+    Exactly(usize),
+    AtLeast(usize),
 }
 
-impl Row {
-    fn new() -> Row {
-        Default::default()
+impl NumberCount {
+    pub fn matches(&self, count: usize) -> bool {
+        match self {
+            NumberCount::Exactly(n) => count == *n,
+            NumberCount::AtLeast(n) => count >= *n,
+        }
     }
+}
 
-    fn is_number_adjacent_to_symbol(&self, number: &Number) -> bool {
-        for symbol in self.symbols.iter() {
-            // Note: indices are signed so we can go negative here without Rust panicking
-            if number.is_index_adjacent(symbol.index) {
-                return true;
-            }
-        }
+#[derive(Clone, Copy, Debug)]
+pub enum Aggregate {
+    Sum,
+    Product,
+}
 
-        false
+impl Aggregate {
+    pub fn apply<'a>(&self, numbers: impl IntoIterator<Item = &'a Number>) -> u64 {
+        let values = numbers.into_iter().map(|number| number.value as u64);
+        match self {
+            Aggregate::Sum => values.sum(),
+            Aggregate::Product => values.product(),
+        }
     }
+}
 
-    // Note: does not empty output first
-    fn collect_numbers_symbol_is_adjacent_to(&self, symbol: &Symbol, output: &mut Vec<Number>) {
-        for number in self.numbers.iter() {
-            if number.is_index_adjacent(symbol.index) {
-                output.push(number.clone());
-            }
+// Symbols from `symbols` with a matching number of neighbouring numbers, each reduced to a
+// single value. The gears in part 2 are '*' with exactly two numbers, multiplied together
+#[derive(Clone, Debug)]
+pub struct SymbolQuery {
+    pub symbols: SymbolSet,
+    pub numbers: NumberCount,
+    pub aggregate: Aggregate,
+}
+
+impl SymbolQuery {
+    pub fn gears() -> SymbolQuery {
+        SymbolQuery {
+            symbols: SymbolSet::Only(vec!['*']),
+            numbers: NumberCount::Exactly(2),
+            aggregate: Aggregate::Product,
         }
     }
+
+    pub fn evaluate<'a>(&self, schematic: &'a Schematic) -> Vec<(&'a Symbol, u64)> {
+        schematic
+            .symbols_with_numbers(&self.symbols, self.numbers)
+            .into_iter()
+            .map(|(symbol, numbers)| (symbol, self.aggregate.apply(numbers)))
+            .collect()
+    }
+
+    pub fn total(&self, schematic: &Schematic) -> u64 {
+        self.evaluate(schematic).iter().map(|(_, value)| value).sum()
+    }
 }
 
-impl FromStr for Row {
-    type Err = std::io::Error;
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    // The index into `numbers` of the number covering each cell, if any
+    number_ids: Grid<Option<usize>>,
+}
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut output: Row = Row::new();
+impl Schematic {
+    pub fn is_adjacent_to(&self, number: &Number, symbols: &SymbolSet) -> bool {
+        number.positions().any(|position| {
+            self.grid
+                .neighbours_8(position)
+                .any(|neighbour| symbols.contains(self.grid[neighbour]))
+        })
+    }
 
-        enum CharType {
-            Number,
-            Symbol,
-            Null,
-        }
+    pub fn is_part_number(&self, number: &Number) -> bool {
+        self.is_adjacent_to(number, &SymbolSet::Any)
+    }
 
-        let mut last_char_type = CharType::Null;
-
-        for (index, char) in input.chars().enumerate() {
-            last_char_type = match char {
-                '.' => CharType::Null,
-                c if c.is_digit(10) => {
-                    if matches!(last_char_type, CharType::Number) {
-                        // The previous char was a digit of this same number, accumulate onto it
-                        let last_number = &mut output.numbers.last_mut().unwrap();
-                        last_number.end_index = index as i64;
-                        last_number.value = last_number.value * 10 + char.to_digit(10).unwrap();
-                    } else {
-                        // If this is the start of a new number then add that to the row
-                        output.numbers.push(Number::new(
-                            char.to_digit(10).unwrap(),
-                            index as i64,
-                            index as i64,
-                        ));
+    pub fn numbers_adjacent_to_symbols(&self, symbols: &SymbolSet) -> Vec<&Number> {
+        self.numbers
+            .iter()
+            .filter(|number| self.is_adjacent_to(number, symbols))
+            .collect()
+    }
+
+    pub fn symbols_with_numbers(&self, symbols: &SymbolSet, count: NumberCount) -> Vec<(&Symbol, Vec<&Number>)> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbols.contains(symbol.value))
+            .map(|symbol| (symbol, self.numbers_adjacent_to(symbol.position)))
+            .filter(|(_, numbers)| count.matches(numbers.len()))
+            .collect()
+    }
+
+    pub fn number_at(&self, position: Position) -> Option<&Number> {
+        let index = (*self.number_ids.get(position)?)?;
+        Some(&self.numbers[index])
+    }
+
+    // Each number is only returned once even when several of its digits touch the position
+    pub fn numbers_adjacent_to(&self, position: Position) -> Vec<&Number> {
+        let mut numbers: Vec<&Number> = self
+            .grid
+            .neighbours_8(position)
+            .filter_map(|neighbour| self.number_at(neighbour))
+            .collect();
+        numbers.sort_by_key(|number| number.id());
+        numbers.dedup_by_key(|number| number.id());
+        numbers
+    }
+}
+
+// The digits of a number as they appear in the input, for locating errors
+fn number_text(input: &str, row: usize, start_col: usize, end_col: usize) -> &str {
+    let line = lines(input).nth(row).unwrap_or(input);
+    let mut offsets = line.char_indices().map(|(offset, _)| offset).chain([line.len()]);
+    let start = offsets.nth(start_col).unwrap_or(0);
+    let end = offsets.nth(end_col - start_col).unwrap_or(line.len());
+    &line[start..end]
+}
+
+impl FromStr for Schematic {
+    type Err = GenericError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let grid: Grid<char> = input.parse()?;
+        let mut numbers: Vec<Number> = vec![];
+        let mut symbols: Vec<Symbol> = vec![];
+        let mut number_ids = grid.map(|_| None);
+
+        for (position, char) in grid.iter() {
+            let (row, col) = position;
+            if let Some(digit) = char.to_digit(10) {
+                match numbers.last_mut() {
+                    // The previous cell was a digit of this same number, accumulate onto it
+                    Some(number) if number.row == row && number.end_col + 1 == col => {
+                        number.end_col = col;
+                        let value = number.value.checked_mul(10).and_then(|value| value.checked_add(digit));
+                        number.value = value.ok_or_else(|| {
+                            let error = GenericError::BasicError(String::from("number is too large"));
+                            error.parsing("day3::Schematic", input, number_text(input, row, number.start_col, col))
+                        })?;
                     }
-                    CharType::Number
+                    _ => numbers.push(Number {
+                        value: digit,
+                        row,
+                        start_col: col,
+                        end_col: col,
+                    }),
                 }
-                c => {
-                    output.symbols.push(Symbol::new(c, index as i64));
-                    CharType::Symbol
-                }
-            };
+                number_ids[position] = Some(numbers.len() - 1);
+            } else if is_symbol(*char) {
+                symbols.push(Symbol {
+                    value: *char,
+                    position,
+                });
+            }
         }
 
-        Ok(output)
+        Ok(Schematic {
+            grid,
+            numbers,
+            symbols,
+            number_ids,
+        })
     }
 }
 
-fn part_1(input_path: &str) -> std::io::Result<u32> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
+// What each cell of the schematic turned out to be, according to the same queries the parts use
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CellKind {
+    Empty,
+    PartNumber,
+    OtherNumber,
+    Gear,
+    Symbol,
+}
+
+impl CellKind {
+    fn ansi_code(&self) -> Option<&'static str> {
+        match self {
+            CellKind::Empty => None,
+            CellKind::PartNumber => Some("\x1b[1;32m"),
+            CellKind::OtherNumber => Some("\x1b[31m"),
+            CellKind::Gear => Some("\x1b[1;33m"),
+            CellKind::Symbol => Some("\x1b[36m"),
+        }
+    }
+
+    fn html_class(&self) -> Option<&'static str> {
+        match self {
+            CellKind::Empty => None,
+            CellKind::PartNumber => Some("part"),
+            CellKind::OtherNumber => Some("other"),
+            CellKind::Gear => Some("gear"),
+            CellKind::Symbol => Some("symbol"),
+        }
+    }
+}
 
-    let mut result = 0;
-    let mut rows: Vec<Row> = Vec::new();
+pub struct Gear<'a> {
+    pub symbol: &'a Symbol,
+    pub numbers: Vec<&'a Number>,
+    pub ratio: u64,
+}
 
-    for line in reader.lines() {
-        let unwrapped_line = line?;
-        rows.push(Row::from_str(&unwrapped_line.as_str())?);
+impl Gear<'_> {
+    fn describe(&self) -> String {
+        let factors: Vec<String> = self.numbers.iter().map(|number| number.value.to_string()).collect();
+        format!("{:?} {} = {}", self.symbol.position, factors.join(" * "), self.ratio)
     }
+}
 
-    for (index, row) in rows.iter().enumerate() {
-        for number in row.numbers.iter() {
-            // Check number against previous row
-            if index > 0 && rows[index - 1].is_number_adjacent_to_symbol(number) {
-                result += number.value;
-                continue;
+impl Schematic {
+    pub fn gears(&self) -> Vec<Gear<'_>> {
+        let query = SymbolQuery::gears();
+        self.symbols_with_numbers(&query.symbols, query.numbers)
+            .into_iter()
+            .map(|(symbol, numbers)| Gear {
+                symbol,
+                ratio: query.aggregate.apply(numbers.iter().copied()),
+                numbers,
+            })
+            .collect()
+    }
+
+    pub fn cell_kinds(&self) -> Grid<CellKind> {
+        let mut kinds = self.grid.map(|_| CellKind::Empty);
+        for number in self.numbers.iter() {
+            let kind = match self.is_part_number(number) {
+                true => CellKind::PartNumber,
+                false => CellKind::OtherNumber,
+            };
+            for position in number.positions() {
+                kinds[position] = kind;
             }
+        }
+        for symbol in self.symbols.iter() {
+            kinds[symbol.position] = CellKind::Symbol;
+        }
+        for gear in self.gears() {
+            kinds[gear.symbol.position] = CellKind::Gear;
+        }
+        kinds
+    }
+}
 
-            // Check number against this row
-            if row.is_number_adjacent_to_symbol(number) {
-                result += number.value;
-                continue;
+fn escape_html(c: char) -> String {
+    match c {
+        '<' => String::from("&lt;"),
+        '>' => String::from("&gt;"),
+        '&' => String::from("&amp;"),
+        '"' => String::from("&quot;"),
+        c => c.to_string(),
+    }
+}
+
+// Runs of cells of the same kind share one colour, gears are always on their own so they can
+// carry their ratio
+fn render_rows(schematic: &Schematic, format: RenderFormat, gear_titles: &[(Position, String)]) -> String {
+    let kinds = schematic.cell_kinds();
+    let mut output = String::new();
+
+    let open = |kind: CellKind, position: Position| match format {
+        RenderFormat::Ansi => kind.ansi_code().unwrap_or_default().to_string(),
+        RenderFormat::Html => {
+            let title = gear_titles.iter().find(|(gear, _)| *gear == position);
+            match (kind.html_class(), title) {
+                (Some(class), Some((_, title))) => format!("<span class=\"{}\" title=\"{}\">", class, title),
+                (Some(class), None) => format!("<span class=\"{}\">", class),
+                (None, _) => String::new(),
+            }
+        }
+    };
+    let close = |kind: Option<CellKind>| match (format, kind) {
+        (_, None | Some(CellKind::Empty)) => "",
+        (RenderFormat::Ansi, _) => "\x1b[0m",
+        (RenderFormat::Html, _) => "</span>",
+    };
+
+    for (row, cells) in schematic.grid.rows().enumerate() {
+        let mut current: Option<CellKind> = None;
+        for (col, c) in cells.iter().enumerate() {
+            let kind = kinds[(row, col)];
+            if current != Some(kind) || kind == CellKind::Gear {
+                output.push_str(close(current));
+                output.push_str(&open(kind, (row, col)));
+                current = Some(kind);
+            }
+            match format {
+                RenderFormat::Ansi => output.push(*c),
+                RenderFormat::Html => output.push_str(&escape_html(*c)),
             }
+        }
+        output.push_str(close(current));
+        output.push('\n');
+    }
 
-            // Check number against next row
-            if index < (rows.len() - 1) && rows[index + 1].is_number_adjacent_to_symbol(number) {
-                result += number.value;
-                continue;
+    output
+}
+
+const HTML_STYLE: &str = "<style>\n\
+    pre { background: #0f0f23; color: #666; }\n\
+    .part { color: #00cc00; font-weight: bold; }\n\
+    .other { color: #ff5555; }\n\
+    .gear { color: #ffff66; font-weight: bold; }\n\
+    .symbol { color: #66ccff; }\n\
+</style>\n";
+
+// Part numbers are green, numbers that aren't counted red, gears yellow and other symbols cyan.
+// Gear ratios are listed underneath, and in HTML also shown when hovering over the gear
+pub fn render_schematic(schematic: &Schematic, format: RenderFormat) -> String {
+    let gears = schematic.gears();
+    let descriptions: Vec<(Position, String)> = gears.iter().map(|gear| (gear.symbol.position, gear.describe())).collect();
+
+    match format {
+        RenderFormat::Ansi => {
+            let mut output = render_rows(schematic, format, &[]);
+            output.push_str(&format!("\nGears ({}):\n", gears.len()));
+            for (_, description) in descriptions.iter() {
+                output.push_str(&format!("  {}\n", description));
+            }
+            output
+        }
+        RenderFormat::Html => {
+            let mut output = String::from(HTML_STYLE);
+            output.push_str(&format!("<pre>\n{}</pre>\n<ul>\n", render_rows(schematic, format, &descriptions)));
+            for (_, description) in descriptions.iter() {
+                output.push_str(&format!("  <li>{}</li>\n", description));
             }
+            output.push_str("</ul>\n");
+            output
         }
     }
+}
 
-    Ok(result)
+pub fn render(input: &str, format: RenderFormat) -> GenericResult<String> {
+    Ok(render_schematic(&Schematic::from_str(input)?, format))
 }
 
-fn part_2(input_path: &str) -> std::io::Result<u32> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
+fn part_1(schematic: &Schematic) -> u64 {
+    Aggregate::Sum.apply(schematic.numbers_adjacent_to_symbols(&SymbolSet::Any))
+}
+
+fn part_2(schematic: &Schematic) -> u64 {
+    schematic.gears().iter().map(|gear| gear.ratio).sum()
+}
 
-    let mut result = 0;
-    let mut rows: Vec<Row> = Vec::new();
+impl Solution for Day3 {
+    type Parsed = Schematic;
+    type Answer = u64;
 
-    for line in reader.lines() {
-        let unwrapped_line = line?;
-        rows.push(Row::from_str(&unwrapped_line.as_str())?);
+    fn parse(input: &str) -> GenericResult<Schematic> {
+        Schematic::from_str(input)
     }
 
-    for (index, row) in rows.iter().enumerate() {
-        for symbol in row.symbols.iter() {
-            if symbol.value != '*' {
-                continue;
-            }
+    fn part_1(schematic: &Schematic) -> GenericResult<u64> {
+        Ok(part_1(schematic))
+    }
 
-            let mut numbers_adjacent_to_symbol: Vec<Number> = Vec::new();
+    fn part_2(schematic: &Schematic) -> GenericResult<u64> {
+        Ok(part_2(schematic))
+    }
+}
 
-            // Check against previous row
-            if index > 0 {
-                rows[index - 1].collect_numbers_symbol_is_adjacent_to(symbol, &mut numbers_adjacent_to_symbol);
-            }
+#[test]
+fn test_parsing_single_line() -> GenericResult<()> {
+    let schematic = Schematic::from_str(".....+.58.")?;
+    assert_eq!(schematic.numbers, vec![Number { value: 58, row: 0, start_col: 7, end_col: 8 }]);
+    assert_eq!(schematic.symbols, vec![Symbol { value: '+', position: (0, 5) }]);
+    Ok(())
+}
 
-            // Check against this row
-            row.collect_numbers_symbol_is_adjacent_to(symbol, &mut numbers_adjacent_to_symbol);
+#[test]
+fn test_numbers_do_not_wrap_between_rows() -> GenericResult<()> {
+    let schematic = Schematic::from_str("..1\n2..")?;
+    assert_eq!(schematic.numbers.iter().map(|number| number.value).collect::<Vec<u32>>(), vec![1, 2]);
+    Ok(())
+}
 
-            // Check against next row
-            if index < (rows.len() - 1) {
-                rows[index + 1].collect_numbers_symbol_is_adjacent_to(symbol, &mut numbers_adjacent_to_symbol);
-            }
+#[test]
+fn test_parts_on_parsed_rows() -> GenericResult<()> {
+    let schematic = Day3::parse("467..114..\n...*......\n..35..633.")?;
+    assert_eq!(part_1(&schematic), 467 + 35);
+    assert_eq!(part_2(&schematic), 467 * 35);
+    Ok(())
+}
 
-            if numbers_adjacent_to_symbol.len() == 2 {
-                result += numbers_adjacent_to_symbol[0].value * numbers_adjacent_to_symbol[1].value;
-            }
-        }
-    }
+#[test]
+fn test_part_1_example() -> GenericResult<()> {
+    assert_eq!(Day3::part_1(&Day3::parse_file("test_data/day3/example.txt")?)?, 4361);
+    Ok(())
+}
 
-    Ok(result)
+#[test]
+fn test_part_2_example() -> GenericResult<()> {
+    assert_eq!(Day3::part_2(&Day3::parse_file("test_data/day3/example.txt")?)?, 467835);
+    Ok(())
+}
+
+#[test]
+fn test_queries_over_symbol_sets() -> GenericResult<()> {
+    let schematic = Schematic::from_str("2.3.4\n.*.#.\n5...6")?;
+    let values = |numbers: Vec<&Number>| numbers.iter().map(|number| number.value).collect::<Vec<u32>>();
+
+    assert_eq!(values(schematic.numbers_adjacent_to_symbols(&SymbolSet::Only(vec!['#']))), vec![3, 4, 6]);
+    assert_eq!(values(schematic.numbers_adjacent_to_symbols(&SymbolSet::Any)), vec![2, 3, 4, 5, 6]);
+
+    let stars = SymbolSet::Only(vec!['*']);
+    assert_eq!(schematic.symbols_with_numbers(&stars, NumberCount::Exactly(2)).len(), 0);
+    assert_eq!(schematic.symbols_with_numbers(&stars, NumberCount::AtLeast(2)).len(), 1);
+
+    let query = SymbolQuery {
+        symbols: SymbolSet::Any,
+        numbers: NumberCount::AtLeast(3),
+        aggregate: Aggregate::Sum,
+    };
+    assert_eq!(query.evaluate(&schematic).iter().map(|(symbol, value)| (symbol.value, *value)).collect::<Vec<(char, u64)>>(), vec![('*', 10), ('#', 13)]);
+    assert_eq!(query.total(&schematic), 23);
+    Ok(())
+}
+
+#[test]
+fn test_empty_and_single_row_schematics() -> GenericResult<()> {
+    let empty = Schematic::from_str("")?;
+    assert_eq!((part_1(&empty), part_2(&empty)), (0, 0));
+
+    let single_row = Schematic::from_str("12*34..5")?;
+    assert_eq!(part_1(&single_row), 12 + 34);
+    assert_eq!(part_2(&single_row), 12 * 34);
+    Ok(())
 }
 
 #[test]
-fn test_parsing_single_line() -> std::io::Result<()> {
-    let mut test_row = Row::new();
-    test_row.numbers.push(Number::new(58, 7, 8));
-    test_row.symbols.push(Symbol::new('+', 5));
-    assert_eq!(Row::from_str(".....+.58.").unwrap(), test_row);
+fn test_equal_numbers_are_kept_apart() -> GenericResult<()> {
+    let schematic = Schematic::from_str("12.12\n..*..")?;
+    let numbers = schematic.numbers_adjacent_to((1, 2));
+    assert_eq!(numbers.iter().map(|number| number.id()).collect::<Vec<NumberId>>(), vec![
+        NumberId { row: 0, start_col: 0, end_col: 1 },
+        NumberId { row: 0, start_col: 3, end_col: 4 },
+    ]);
+    assert_eq!(part_2(&schematic), 144);
     Ok(())
 }
 
 #[test]
-fn test_part_1_example() -> std::io::Result<()> {
-    assert_eq!(part_1("test_data/day3/example.txt").unwrap(), 4361);
+fn test_adjacency_at_the_edges_and_diagonals() -> GenericResult<()> {
+    // Symbols in the corners touching numbers only diagonally
+    let schematic = Schematic::from_str("#....\n.1.2.\n....$")?;
+    assert_eq!(part_1(&schematic), 1 + 2);
+    assert_eq!(schematic.numbers_adjacent_to((2, 4)).iter().map(|number| number.value).collect::<Vec<u32>>(), vec![2]);
+    assert_eq!(schematic.numbers_adjacent_to((0, 2)).len(), 2);
+
+    let schematic = Schematic::from_str("....7\n...+.\n9....")?;
+    assert_eq!(part_1(&schematic), 7);
+    assert_eq!(schematic.number_at((0, 4)).map(|number| number.value), Some(7));
+    assert_eq!(schematic.number_at((3, 0)), None);
+
+    // A long number is adjacent through any of its digits, but only counted once
+    let schematic = Schematic::from_str("1234\n...*")?;
+    assert_eq!(schematic.numbers_adjacent_to((1, 3)).len(), 1);
+    assert_eq!(part_1(&schematic), 1234);
     Ok(())
 }
 
 #[test]
-fn test_part_2_example() -> std::io::Result<()> {
-    assert_eq!(part_2("test_data/day3/example.txt").unwrap(), 467835);
+fn test_cell_kinds_follow_the_solver() -> GenericResult<()> {
+    let schematic = Schematic::from_str("12*34\n.....\n5.#..")?;
+    let kinds = schematic.cell_kinds();
+    assert_eq!(kinds.row(0), Some(&[CellKind::PartNumber, CellKind::PartNumber, CellKind::Gear, CellKind::PartNumber, CellKind::PartNumber][..]));
+    assert_eq!(kinds[(2, 0)], CellKind::OtherNumber);
+    assert_eq!(kinds[(2, 2)], CellKind::Symbol);
+    assert_eq!(kinds[(1, 0)], CellKind::Empty);
     Ok(())
 }
 
-pub fn run(input_path: &String) -> std::io::Result<()> {
-    println!("Part one result: {}", part_1(&input_path)?);
-    println!("Part two result: {}", part_2(&input_path)?);
+#[test]
+fn test_render_formats() -> GenericResult<()> {
+    let ansi = render("12*34\n5.&..", RenderFormat::Ansi)?;
+    assert!(ansi.starts_with("\x1b[1;32m12\x1b[0m\x1b[1;33m*\x1b[0m\x1b[1;32m34\x1b[0m\n\x1b[31m5\x1b[0m."));
+    assert!(ansi.ends_with("Gears (1):\n  (0, 2) 12 * 34 = 408\n"));
+
+    let html = render("12*34\n5.&..", RenderFormat::Html)?;
+    assert!(html.contains("<span class=\"gear\" title=\"(0, 2) 12 * 34 = 408\">*</span>"));
+    assert!(html.contains("<span class=\"symbol\">&amp;</span>"));
     Ok(())
 }
+
+#[test]
+fn test_numbers_too_large_are_located_errors() {
+    let error = Day3::parse(".............\n.é99999999999").err().unwrap();
+    assert!(error.render().contains("--> <input>:2:3"), "{}", error.render());
+    assert!(error.render().contains("^^^^^^^^^^"), "{}", error.render());
+}
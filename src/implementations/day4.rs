@@ -1,14 +1,16 @@
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
 use std::str::FromStr;
 
-use crate::utility;
+use crate::implementations::Solution;
+use crate::utility::generic_error::{GenericError, GenericResult, ParseContext};
+use crate::utility::input::{lines, numbers};
 
-struct Card {
+pub struct Day4;
+
+pub struct Card {
     _id: u32,
-    winning_numbers: Vec<u32>,
-    your_numbers: Vec<u32>,
-    num_matches: usize,
+    pub winning_numbers: Vec<u32>,
+    pub your_numbers: Vec<u32>,
+    pub num_matches: usize,
 }
 
 impl Card {
@@ -23,28 +25,25 @@ impl Card {
 }
 
 impl FromStr for Card {
-    type Err = ParseIntError;
+    type Err = GenericError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let split_str: Vec<&str> = input.split(&[':', '|']).collect();
-        let card_id = sscanf::sscanf!(split_str[0], "Card{:/[\x20]+/}{}", str, usize).unwrap();
+        let missing_separator = |separator: char| {
+            GenericError::BasicError(format!("expected '{}' in card", separator)).parsing("day4::Card", input, input)
+        };
+        let (card_str, numbers_str) = input.split_once(':').ok_or_else(|| missing_separator(':'))?;
+        let (winning_str, your_str) = numbers_str.split_once('|').ok_or_else(|| missing_separator('|'))?;
 
-        let mut output: Card = Card::new(card_id.1.try_into().unwrap());
+        let card_id = sscanf::sscanf!(card_str, "Card{:/[\x20]+/}{}", str, u32).parsing("day4::Card", input, card_str)?;
 
-        for number_str in split_str[1].split_whitespace() {
-            if number_str.len() == 0 {
-                continue;
-            }
+        let mut output: Card = Card::new(card_id.1);
 
-            output.winning_numbers.push(number_str.parse()?);
+        for number in numbers(winning_str) {
+            output.winning_numbers.push(number.parsing("day4::Card", input, winning_str)?);
         }
 
-        for number_str in split_str[2].split_whitespace() {
-            if number_str.len() == 0 {
-                continue;
-            }
-
-            output.your_numbers.push(number_str.parse()?);
+        for number in numbers(your_str) {
+            output.your_numbers.push(number.parsing("day4::Card", input, your_str)?);
         }
 
         for winning_number in output.winning_numbers.iter() {
@@ -59,43 +58,21 @@ impl FromStr for Card {
     }
 }
 
-fn part_1(input_path: &str) -> utility::generic_error::GenericResult<u32> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
-
+fn part_1(cards: &[Card]) -> u32 {
     let mut result = 0;
 
-    let mut cards: Vec<Card> = vec![];
-
-    for line in reader.lines() {
-        let unwrapped_line = line?;
-
-        cards.push(Card::from_str(unwrapped_line.as_str())?)
-    }
-
     for card in cards {
         if card.num_matches > 0 {
             result += 1 << (card.num_matches - 1);
         }
     }
 
-    Ok(result)
+    result
 }
 
-fn part_2(input_path: &str) -> utility::generic_error::GenericResult<u32> {
-    let file_handle = std::fs::File::open(input_path)?;
-    let reader = BufReader::new(file_handle);
-
+fn part_2(cards: &[Card]) -> u32 {
     let mut result = 0;
 
-    let mut cards: Vec<Card> = vec![];
-
-    for line in reader.lines() {
-        let unwrapped_line = line?;
-
-        cards.push(Card::from_str(unwrapped_line.as_str()).unwrap())
-    }
-
     let mut number_of_each_card:Vec<u32> = vec![1;cards.len()];
 
     for (index, card) in cards.iter().enumerate() {
@@ -109,23 +86,53 @@ fn part_2(input_path: &str) -> utility::generic_error::GenericResult<u32> {
         result += number_of_this_card;
     }
 
-    Ok(result)
+    result
+}
+
+impl Solution for Day4 {
+    type Parsed = Vec<Card>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> GenericResult<Vec<Card>> {
+        lines(input)
+            .map(|line| Card::from_str(line).parsing("day4::Card", input, line))
+            .collect()
+    }
+
+    fn part_1(cards: &Vec<Card>) -> GenericResult<u32> {
+        Ok(part_1(cards))
+    }
+
+    fn part_2(cards: &Vec<Card>) -> GenericResult<u32> {
+        Ok(part_2(cards))
+    }
 }
 
 #[test]
-pub fn run_test_1() -> std::io::Result<()> {
-    assert_eq!(part_1("test_data/day4/example.txt").unwrap(), 13);
+pub fn run_test_1() -> GenericResult<()> {
+    assert_eq!(Day4::part_1(&Day4::parse_file("test_data/day4/example.txt")?)?, 13);
     Ok(())
 }
 
 #[test]
-pub fn run_test_2() -> std::io::Result<()> {
-    assert_eq!(part_2("test_data/day4/example.txt").unwrap(), 30);
+pub fn run_test_2() -> GenericResult<()> {
+    assert_eq!(Day4::part_2(&Day4::parse_file("test_data/day4/example.txt")?)?, 30);
     Ok(())
 }
 
-pub fn run(input_path: &String) -> utility::generic_error::GenericResult<()> {
-    println!("Part one result: {}", part_1(&input_path)?);
-    println!("Part two result: {}", part_2(&input_path)?);
+#[test]
+pub fn test_malformed_cards_are_errors() {
+    assert!(Card::from_str("Card x: 1 2 | 3 4").is_err());
+    assert!(Card::from_str("Card 1: 1 2 3 4").is_err());
+    assert!(Card::from_str("Card 1 1 2 | 3 4").is_err());
+    assert!(Card::from_str("Card 1: 1 2 | 3 four").is_err());
+}
+
+#[test]
+pub fn test_parsed_cards_are_shared_by_both_parts() -> GenericResult<()> {
+    let cards = Day4::parse("Card 1: 1 2 | 1 2\nCard 2: 3 | 4")?;
+    assert_eq!(cards.iter().map(|card| card.num_matches).collect::<Vec<usize>>(), vec![2, 0]);
+    assert_eq!(part_1(&cards), 2);
+    assert_eq!(part_2(&cards), 1 + 2);
     Ok(())
 }
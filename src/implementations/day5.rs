@@ -1,10 +1,14 @@
 use std::str::FromStr;
-use strum::{EnumCount, EnumIter, EnumString};
+use strum::{Display, EnumCount, EnumIter, EnumString, IntoEnumIterator};
 
-use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::implementations::Solution;
+use crate::utility::generic_error::{GenericError, GenericResult, ParseContext};
+use crate::utility::input::{lines, numbers, sections};
 
-#[derive(EnumCount, EnumIter, Copy, Clone, PartialEq, EnumString)]
-enum EntityType {
+pub struct Day5;
+
+#[derive(EnumCount, EnumIter, Copy, Clone, PartialEq, EnumString, Display)]
+pub enum EntityType {
     #[strum(serialize = "seed")]
     Seed,
     #[strum(serialize = "soil")]
@@ -23,30 +27,30 @@ enum EntityType {
     Location,
 }
 
-struct Mapping {
+pub struct Mapping {
     source: usize,      // start index of source
     destination: usize, // start index of destination
     span: usize,        // number of entries across both source and destination
 }
 
 impl Mapping {
-    fn source_begin(&self) -> usize {
+    pub fn source_begin(&self) -> usize {
         self.source
     }
 
-    fn source_end(&self) -> usize {
+    pub fn source_end(&self) -> usize {
         self.source + self.span
     }
 
-    fn destination_begin(&self) -> usize {
+    pub fn destination_begin(&self) -> usize {
         self.destination
     }
 
-    fn destination_end(&self) -> usize {
+    pub fn destination_end(&self) -> usize {
         self.destination + self.span
     }
 
-    fn do_mapping(&self, source: usize) -> usize {
+    pub fn do_mapping(&self, source: usize) -> usize {
         source - self.source + self.destination
     }
 }
@@ -55,7 +59,7 @@ impl FromStr for Mapping {
     type Err = GenericError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let parsed = sscanf::sscanf!(input, "{} {} {}", usize, usize, usize)?;
+        let parsed = sscanf::sscanf!(input, "{} {} {}", usize, usize, usize).parsing("day5::Mapping", input, input)?;
         Ok(Mapping {
             source: parsed.1,
             destination: parsed.0,
@@ -64,14 +68,14 @@ impl FromStr for Mapping {
     }
 }
 
-struct MappingGroup {
+pub struct MappingGroup {
     source_type: EntityType,
     destination_type: EntityType,
     mappings: Vec<Mapping>,
 }
 
 impl MappingGroup {
-    fn do_mapping(&self, input: usize) -> usize {
+    pub fn do_mapping(&self, input: usize) -> usize {
         for mapping in self.mappings.iter() {
             if input >= mapping.source && input < mapping.source + mapping.span {
                 return mapping.do_mapping(input);
@@ -80,7 +84,7 @@ impl MappingGroup {
         input
     }
 
-    fn do_range_mapping(&self, input: &std::ops::Range<usize>) -> Vec<std::ops::Range<usize>> {
+    pub fn do_range_mapping(&self, input: &std::ops::Range<usize>) -> Vec<std::ops::Range<usize>> {
         let mut unmapped: Vec<std::ops::Range<usize>> = vec![input.clone()];
         let mut output: Vec<std::ops::Range<usize>> = vec![];
 
@@ -141,13 +145,13 @@ impl FromStr for MappingGroup {
         let mut destination_type: Option<EntityType> = None;
         let mut mappings: Vec<Mapping> = vec![];
 
-        for (index, line) in input.lines().enumerate() {
+        for (index, line) in lines(input).enumerate() {
             if index == 0 {
-                let map_types = sscanf::scanf!(line, "{}-to-{} map:", str, str)?;
-                source_type = Some(EntityType::from_str(map_types.0)?);
-                destination_type = Some(EntityType::from_str(map_types.1)?);
+                let map_types = sscanf::scanf!(line, "{}-to-{} map:", str, str).parsing("day5::MappingGroup", input, line)?;
+                source_type = Some(EntityType::from_str(map_types.0).parsing("day5::MappingGroup", input, map_types.0)?);
+                destination_type = Some(EntityType::from_str(map_types.1).parsing("day5::MappingGroup", input, map_types.1)?);
             } else {
-                mappings.push(Mapping::from_str(line)?);
+                mappings.push(Mapping::from_str(line).parsing("day5::MappingGroup", input, line)?);
             }
         }
 
@@ -160,18 +164,18 @@ impl FromStr for MappingGroup {
         Ok(MappingGroup {
             source_type: source_type.unwrap(),
             destination_type: destination_type.unwrap(),
-            mappings: mappings,
+            mappings,
         })
     }
 }
 
-struct ProblemSet {
+pub struct ProblemSet {
     mapping_groups: Vec<MappingGroup>,
-    seeds: Vec<usize>,
+    pub seeds: Vec<usize>,
 }
 
 impl ProblemSet {
-    fn get_location_for_seed(&self, seed: usize) -> usize {
+    pub fn get_location_for_seed(&self, seed: usize) -> usize {
         let mut result: usize = seed;
         for mapping_group in self.mapping_groups.iter() {
             result = mapping_group.do_mapping(result);
@@ -179,7 +183,8 @@ impl ProblemSet {
         result
     }
 
-    fn get_min_location_for_seed_range(&self, first_seed: usize, seed_count: usize) -> usize {
+    pub fn get_min_location_for_seed_range(&self, first_seed: usize, seed_count: usize) -> usize {
+        #[allow(clippy::single_range_in_vec_init)]
         let mut ranges: Vec<std::ops::Range<usize>> = vec![first_seed..(first_seed + seed_count)];
         for mapping_group in self.mapping_groups.iter() {
             let mut new_ranges: Vec<std::ops::Range<usize>> = vec![];
@@ -206,56 +211,65 @@ impl FromStr for ProblemSet {
         };
         output.mapping_groups.reserve(EntityType::COUNT - 1);
 
-        let replaced_input = input.replace("\r\n", "\n");
-        let file_sections: Vec<&str> = replaced_input.split("\n\n").collect();
+        let mut file_sections = sections(input);
 
         // Parse seeds
         {
-            let split_str: Vec<&str> = file_sections[0].split_whitespace().collect();
-            assert!(split_str[0] == "seeds:");
-
-            for seed_str in &split_str[1..] {
-                output.seeds.push(seed_str.parse()?);
+            let seeds_section = file_sections.next().unwrap_or(input);
+            let seeds_str = seeds_section.strip_prefix("seeds:").ok_or_else(|| {
+                let first_line = lines(seeds_section).next().unwrap_or(seeds_section);
+                GenericError::BasicError(String::from("expected the input to start with \"seeds:\""))
+                    .parsing("day5::ProblemSet", input, first_line)
+            })?;
+
+            for seed in numbers(seeds_str) {
+                output.seeds.push(seed.parsing("day5::ProblemSet", input, seeds_str)?);
             }
         }
 
-        // Parse mapping groups
-        for section in &file_sections[1..] {
-            output.mapping_groups.push(MappingGroup::from_str(section)?);
-        }
-
-        {
-            // Validate that the sections we have retrieved are in the correct order
-            for (index, mapping_group) in output.mapping_groups.iter().enumerate() {
-                assert!(mapping_group.source_type as usize == index);
-                assert!(mapping_group.destination_type as usize == index + 1);
+        // Parse mapping groups, validating that they are in the correct order
+        let mut expected_types = EntityType::iter().zip(EntityType::iter().skip(1));
+        for section in file_sections {
+            let mapping_group = MappingGroup::from_str(section).parsing("day5::ProblemSet", input, section)?;
+
+            let (source_type, destination_type) = expected_types.next().ok_or_else(|| {
+                GenericError::BasicError(String::from("unexpected map after the location map"))
+                    .parsing("day5::ProblemSet", input, section)
+            })?;
+            if mapping_group.source_type != source_type || mapping_group.destination_type != destination_type {
+                let error = GenericError::BasicError(format!(
+                    "expected the {}-to-{} map here",
+                    source_type, destination_type
+                ));
+                let header = lines(section).next().unwrap_or(section);
+                return Err(error.parsing("day5::ProblemSet", input, header));
             }
+
+            output.mapping_groups.push(mapping_group);
         }
 
         Ok(output)
     }
 }
 
-fn part_1(input_path: &str) -> GenericResult<usize> {
-    let file_contents = std::fs::read_to_string(input_path)?;
-
+fn part_1(problem_set: &ProblemSet) -> usize {
     let mut result = usize::MAX;
 
-    let problem_set = ProblemSet::from_str(file_contents.as_str())?;
-
     for seed in problem_set.seeds.iter() {
         result = std::cmp::min(result, problem_set.get_location_for_seed(*seed));
     }
 
-    Ok(result)
+    result
 }
 
-fn part_2(input_path: &str) -> GenericResult<usize> {
-    let file_contents = std::fs::read_to_string(input_path)?;
-
+fn part_2(problem_set: &ProblemSet) -> GenericResult<usize> {
     let mut result = usize::MAX;
 
-    let problem_set = ProblemSet::from_str(file_contents.as_str())?;
+    if !problem_set.seeds.len().is_multiple_of(2) {
+        return Err(GenericError::BasicError(String::from(
+            "part 2 needs the seeds to be pairs of start and length",
+        )));
+    }
 
     for seed_group in problem_set.seeds.chunks(2) {
         result = std::cmp::min(
@@ -267,9 +281,26 @@ fn part_2(input_path: &str) -> GenericResult<usize> {
     Ok(result)
 }
 
+impl Solution for Day5 {
+    type Parsed = ProblemSet;
+    type Answer = usize;
+
+    fn parse(input: &str) -> GenericResult<ProblemSet> {
+        ProblemSet::from_str(input)
+    }
+
+    fn part_1(problem_set: &ProblemSet) -> GenericResult<usize> {
+        Ok(part_1(problem_set))
+    }
+
+    fn part_2(problem_set: &ProblemSet) -> GenericResult<usize> {
+        part_2(problem_set)
+    }
+}
+
 #[test]
 pub fn run_test_1() -> GenericResult<()> {
-    assert_eq!(part_1("test_data/day5/example.txt")?, 35);
+    assert_eq!(Day5::part_1(&Day5::parse_file("test_data/day5/example.txt")?)?, 35);
     Ok(())
 }
 
@@ -313,12 +344,14 @@ pub fn stress_test_mapping_group() -> GenericResult<()> {
 
 #[test]
 pub fn run_test_2() -> GenericResult<()> {
-    assert_eq!(part_2("test_data/day5/example.txt").unwrap(), 46);
+    assert_eq!(Day5::part_2(&Day5::parse_file("test_data/day5/example.txt")?)?, 46);
     Ok(())
 }
 
-pub fn run(input_path: &String) -> GenericResult<()> {
-    println!("Part one result: {}", part_1(&input_path)?);
-    println!("Part two result: {}", part_2(&input_path)?);
-    Ok(())
-}
+#[test]
+pub fn test_malformed_problem_sets_are_errors() {
+    assert!(ProblemSet::from_str("seed: 1 2").is_err());
+    assert!(ProblemSet::from_str("seeds: 1 2\n\nsoil-to-fertilizer map:\n1 2 3").is_err());
+    assert!(ProblemSet::from_str("seeds: 1 2\n\nseed-to-soil map:\n1 2").is_err());
+    assert!(Day5::part_2(&ProblemSet::from_str("seeds: 1 2 3").unwrap()).is_err());
+}
\ No newline at end of file
@@ -1,6 +1,10 @@
-use crate::utility::generic_error::GenericResult;
+use crate::implementations::Solution;
+use crate::utility::generic_error::{GenericError, GenericResult, ParseContext};
+use crate::utility::input::lines;
 
-fn count_ways_to_beat(time: f64, distance_to_beat: f64) -> usize {
+pub struct Day6;
+
+pub fn count_ways_to_beat(time: f64, distance_to_beat: f64) -> usize {
     // Classic quadratic equation
     let right_part = (time * time - 4.0 * distance_to_beat).sqrt();
     let lower_bound = (time - right_part) / 2.0;
@@ -10,52 +14,108 @@ fn count_ways_to_beat(time: f64, distance_to_beat: f64) -> usize {
     (upper_bound.ceil() - lower_bound.floor() - 1.0) as usize
 }
 
-fn part_1(input_path: &str) -> GenericResult<usize> {
-    let file_contents = std::fs::read_to_string(input_path)?;
+// Returns the race times and distances without their "Time:" and "Distance:" labels
+fn split_races(input: &str) -> GenericResult<(Vec<&str>, Vec<&str>)> {
+    let mut lines = lines(input);
+    let times = lines.next().and_then(|line| line.strip_prefix("Time:"));
+    let distances = lines.next().and_then(|line| line.strip_prefix("Distance:"));
 
-    let mut result = 1;
+    match (times, distances) {
+        (Some(times), Some(distances)) if times.split_whitespace().count() == distances.split_whitespace().count() => {
+            Ok((times.split_whitespace().collect(), distances.split_whitespace().collect()))
+        }
+        _ => {
+            let error = GenericError::BasicError(String::from(
+                "expected a \"Time:\" line and a \"Distance:\" line with the same number of races",
+            ));
+            Err(error.parsing("day6::Races", input, input))
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Race {
+    pub time: f64,
+    pub distance: f64,
+}
+
+// Part 1 reads each column as its own race, part 2 ignores the spacing and reads one long race
+pub struct Races {
+    pub races: Vec<Race>,
+    pub combined: Race,
+}
+
+fn parse_race(input: &str, time_str: &str, distance_str: &str) -> GenericResult<Race> {
+    Ok(Race {
+        time: time_str.parse().parsing("day6::Race", input, time_str)?,
+        distance: distance_str.parse().parsing("day6::Race", input, distance_str)?,
+    })
+}
+
+fn parse_races(input: &str) -> GenericResult<Races> {
+    let (times, distances) = split_races(input)?;
 
-    let split_str: Vec<&str> = file_contents.split_whitespace().collect();
+    let races = times
+        .iter()
+        .zip(distances.iter())
+        .map(|(time_str, distance_str)| parse_race(input, time_str, distance_str))
+        .collect::<GenericResult<Vec<Race>>>()?;
 
-    let num_races = (split_str.len() / 2) - 1;
+    let time_str = times.concat();
+    let distance_str = distances.concat();
+    let combined = parse_race(input, &time_str, &distance_str)?;
 
-    for race_num in 0..num_races {
-        let time: f64 = split_str[race_num + 1].parse()?;
-        let distance_to_beat: f64 = split_str[race_num + num_races + 2].parse()?;
-        result *= count_ways_to_beat(time, distance_to_beat);
+    Ok(Races { races, combined })
+}
+
+fn part_1(races: &Races) -> usize {
+    let mut result = 1;
+
+    for race in races.races.iter() {
+        result *= count_ways_to_beat(race.time, race.distance);
     }
 
-    Ok(result)
+    result
+}
+
+fn part_2(races: &Races) -> usize {
+    count_ways_to_beat(races.combined.time, races.combined.distance)
 }
 
-fn part_2(input_path: &str) -> GenericResult<usize> {
-    let file_contents = std::fs::read_to_string(input_path)?;
+impl Solution for Day6 {
+    type Parsed = Races;
+    type Answer = usize;
 
-    let split_str: Vec<&str> = file_contents.split_whitespace().collect();
-    let num_races = (split_str.len() / 2) - 1;
-    let time_str = (&split_str[1..(num_races + 1)]).concat();
-    let distance_str = (&split_str[(num_races + 2)..(2 * num_races + 2)]).concat();
+    fn parse(input: &str) -> GenericResult<Races> {
+        parse_races(input)
+    }
 
-    let time: f64 = time_str.parse()?;
-    let distance: f64 = distance_str.parse()?;
+    fn part_1(races: &Races) -> GenericResult<usize> {
+        Ok(part_1(races))
+    }
 
-    Ok(count_ways_to_beat(time, distance))
+    fn part_2(races: &Races) -> GenericResult<usize> {
+        Ok(part_2(races))
+    }
 }
 
 #[test]
 pub fn run_test_1() -> GenericResult<()> {
-    assert_eq!(part_1("test_data/day6/example.txt")?, 288);
+    assert_eq!(Day6::part_1(&Day6::parse_file("test_data/day6/example.txt")?)?, 288);
     Ok(())
 }
 
 #[test]
 pub fn run_test_2() -> GenericResult<()> {
-    assert_eq!(part_2("test_data/day6/example.txt")?, 71503);
+    assert_eq!(Day6::part_2(&Day6::parse_file("test_data/day6/example.txt")?)?, 71503);
     Ok(())
 }
 
-pub fn run(input_path: &String) -> GenericResult<()> {
-    println!("Part one result: {}", part_1(&input_path)?);
-    println!("Part two result: {}", part_2(&input_path)?);
+#[test]
+pub fn test_races_are_parsed_for_both_parts() -> GenericResult<()> {
+    let races = Day6::parse("Time:      7  15\nDistance:  9  40")?;
+    assert_eq!(races.races, vec![Race { time: 7.0, distance: 9.0 }, Race { time: 15.0, distance: 40.0 }]);
+    assert_eq!(races.combined, Race { time: 715.0, distance: 940.0 });
+    assert_eq!(part_1(&races), 4 * 8);
     Ok(())
 }
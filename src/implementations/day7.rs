@@ -1,13 +1,14 @@
 use std::str::FromStr;
-use std::sync::atomic::{AtomicBool, Ordering};
 use strum::{EnumCount, EnumIter, EnumString};
 
-use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::implementations::Solution;
+use crate::utility::generic_error::{GenericError, GenericResult, ParseContext};
+use crate::utility::input::lines;
 
-static JOKERS_ENABLED: AtomicBool = AtomicBool::new(false);
+pub struct Day7;
 
 #[derive(EnumCount, EnumIter, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, EnumString)]
-enum Card {
+pub enum Card {
     #[strum(serialize = "X")] // Note: this is never present, Jacks are turned into Jokers when joker functionality is enabled
     Joker,
     #[strum(serialize = "2")]
@@ -39,7 +40,7 @@ enum Card {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -50,26 +51,41 @@ enum HandType {
 }
 
 #[derive(PartialEq, Eq)]
-struct Hand {
-    cards: [Card; 5],
-    hand_type: HandType,
+pub struct Hand {
+    pub cards: [Card; 5],
+    pub hand_type: HandType,
 }
 
-impl FromStr for Hand {
-    type Err = GenericError;
+fn parse_cards(input: &str) -> GenericResult<[Card; 5]> {
+    let mut cards: [Card; 5] = [Card::Two; 5];
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let jokers_enabled = JOKERS_ENABLED.load(Ordering::Relaxed);
+    if input.chars().count() != cards.len() {
+        let error = GenericError::BasicError(format!("expected {} cards in a hand", cards.len()));
+        return Err(error.parsing("day7::Hand", input, input));
+    }
+
+    for (index, (offset, char)) in input.char_indices().enumerate() {
+        let card_str = &input[offset..offset + char.len_utf8()];
+        cards[index] = match Card::from_str(card_str).parsing("day7::Card", input, card_str)? {
+            // Jokers only come from jacks once jokers are enabled, they are never written down
+            Card::Joker => {
+                let error = GenericError::BasicError(format!("\"{}\" is not a card", card_str));
+                return Err(error.parsing("day7::Card", input, card_str));
+            }
+            card => card,
+        };
+    }
 
-        let mut cards: [Card; 5] = [Card::Two; 5];
+    Ok(cards)
+}
 
-        let mut tmp: [u8; 4] = [0; 4];
-        for (index, char) in input.chars().enumerate() {
-            let new_card = Card::from_str(char.encode_utf8(&mut tmp))?;
-            cards[index] = if new_card == Card::Jack && jokers_enabled {
-                Card::Joker
-            } else {
-                new_card
+impl Hand {
+    // With jokers enabled every Jack becomes a Joker, which is the weakest card but counts
+    // towards whichever card there is most of
+    pub fn new(mut cards: [Card; 5], jokers_enabled: bool) -> GenericResult<Hand> {
+        if jokers_enabled {
+            for card in cards.iter_mut().filter(|card| **card == Card::Jack) {
+                *card = Card::Joker;
             }
         }
 
@@ -78,7 +94,7 @@ impl FromStr for Hand {
         for card in cards {
             let found = card_counts.iter_mut().find(|(test, _)| *test == card);
             match found {
-                Some((_, count)) => *count = *count + 1,
+                Some((_, count)) => *count += 1,
                 None => card_counts.push((card, 1)),
             }
         }
@@ -106,49 +122,63 @@ impl FromStr for Hand {
             [(_, 2), (_, 2), (_, 1)] => HandType::TwoPair,
             [(_, 2), (_, 1), (_, 1), (_, 1)] => HandType::OnePair,
             [(_, 1), (_, 1), (_, 1), (_, 1), (_, 1)] => HandType::HighCard,
-            _ => {
-                assert!(false);
-                HandType::HighCard
-            }
+            _ => return Err(GenericError::BasicError(String::from("could not work out the type of hand"))),
         };
 
-        Ok(Hand {
-            cards: cards,
-            hand_type: hand_type,
-        })
+        Ok(Hand { cards, hand_type })
+    }
+}
+
+impl FromStr for Hand {
+    type Err = GenericError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Hand::new(parse_cards(input)?, false)
     }
 }
 
 impl std::cmp::PartialOrd for Hand {
     fn partial_cmp(&self, other: &Hand) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::cmp::Ord for Hand {
+    fn cmp(&self, other: &Hand) -> std::cmp::Ordering {
         if self.hand_type != other.hand_type {
-            return self.hand_type.partial_cmp(&other.hand_type);
+            return self.hand_type.cmp(&other.hand_type);
         }
 
         let cards = self.cards.iter().zip(other.cards.iter());
         for card in cards {
             if card.0 != card.1 {
-                return card.0.partial_cmp(card.1);
+                return card.0.cmp(card.1);
             }
         }
 
-        Some(std::cmp::Ordering::Equal)
+        std::cmp::Ordering::Equal
     }
 }
 
-impl std::cmp::Ord for Hand {
-    fn cmp(&self, other: &Hand) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
-    }
+pub struct Bid {
+    pub cards: [Card; 5],
+    pub bid: usize,
 }
 
-fn run_internal(input_path: &str) -> GenericResult<usize> {
-    let file_contents = std::fs::read_to_string(input_path)?;
+fn parse_bids(input: &str) -> GenericResult<Vec<Bid>> {
+    let mut bids = vec![];
+    for hand_str in lines(input) {
+        let parsed = sscanf::sscanf!(hand_str, "{} {}", str, usize).parsing("day7::Hand", input, hand_str)?;
+        let cards = parse_cards(parsed.0).parsing("day7::Hand", input, parsed.0)?;
+        bids.push(Bid { cards, bid: parsed.1 });
+    }
+    Ok(bids)
+}
 
+fn run_internal(bids: &[Bid], jokers_enabled: bool) -> GenericResult<usize> {
     let mut hands: Vec<(Hand, usize)> = vec![];
-    for hand_str in file_contents.lines() {
-        let parsed = sscanf::sscanf!(hand_str, "{} {}", str, usize)?;
-        hands.push((Hand::from_str(parsed.0)?, parsed.1));
+    for bid in bids {
+        hands.push((Hand::new(bid.cards, jokers_enabled)?, bid.bid));
     }
 
     hands.sort_by(|(left, _), (right, _)| left.cmp(right));
@@ -162,24 +192,50 @@ fn run_internal(input_path: &str) -> GenericResult<usize> {
     Ok(result)
 }
 
+impl Solution for Day7 {
+    type Parsed = Vec<Bid>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> GenericResult<Vec<Bid>> {
+        parse_bids(input)
+    }
+
+    fn part_1(bids: &Vec<Bid>) -> GenericResult<usize> {
+        run_internal(bids, false)
+    }
+
+    fn part_2(bids: &Vec<Bid>) -> GenericResult<usize> {
+        run_internal(bids, true)
+    }
+}
+
 #[test]
 pub fn run_test_1() -> GenericResult<()> {
-    JOKERS_ENABLED.store(false, Ordering::Relaxed);
-    assert_eq!(run_internal("test_data/day7/example.txt")?, 6440);
+    assert_eq!(Day7::part_1(&Day7::parse_file("test_data/day7/example.txt")?)?, 6440);
     Ok(())
 }
 
 #[test]
 pub fn run_test_2() -> GenericResult<()> {
-    JOKERS_ENABLED.store(true, Ordering::Relaxed);
-    assert_eq!(run_internal("test_data/day7/example.txt")?, 5905);
+    assert_eq!(Day7::part_2(&Day7::parse_file("test_data/day7/example.txt")?)?, 5905);
     Ok(())
 }
 
-pub fn run(input_path: &String) -> GenericResult<()> {
-    JOKERS_ENABLED.store(false, Ordering::Relaxed);
-    println!("Part one result: {}", run_internal(&input_path)?);
-    JOKERS_ENABLED.store(true, Ordering::Relaxed);
-    println!("Part two result: {}", run_internal(&input_path)?);
+#[test]
+pub fn test_malformed_hands_are_errors() {
+    assert!(Hand::from_str("AAAAAA").is_err());
+    assert!(Hand::from_str("AAAA").is_err());
+    assert!(Hand::from_str("AAAAZ").is_err());
+    assert!(Hand::from_str("XXXXX").is_err());
+    assert!(Day7::parse("XXXXX 5").is_err());
+}
+
+#[test]
+pub fn test_jokers_only_change_how_parsed_bids_are_scored() -> GenericResult<()> {
+    let bids = Day7::parse("JJJJ2 10\nKKKK2 1")?;
+    assert!(Hand::new(bids[0].cards, false)?.hand_type == HandType::FourOfAKind);
+    assert!(Hand::new(bids[0].cards, true)?.hand_type == HandType::FiveOfAKind);
+    assert_eq!(Day7::part_1(&bids)?, 10 + 2);
+    assert_eq!(Day7::part_2(&bids)?, 20 + 1);
     Ok(())
 }
@@ -1,7 +1,261 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::utility::config::DayConfig;
+use crate::utility::generic_error::GenericResult;
+
 pub mod day1;
 pub mod day2;
 pub mod day3;
 pub mod day4;
 pub mod day5;
 pub mod day6;
-pub mod day7;
\ No newline at end of file
+pub mod day7;
+
+pub trait Solution {
+    type Parsed;
+    type Answer: Into<Answer>;
+
+    fn parse(input: &str) -> GenericResult<Self::Parsed>;
+
+    // Days with settings read them here, everything else just parses
+    fn parse_configured(input: &str, _config: &DayConfig) -> GenericResult<Self::Parsed> {
+        Self::parse(input)
+    }
+    fn part_1(input: &Self::Parsed) -> GenericResult<Self::Answer>;
+    fn part_2(input: &Self::Parsed) -> GenericResult<Self::Answer>;
+
+    // Anything a part skipped or worked around to get its answer, reported alongside it
+    fn warnings(_input: &Self::Parsed, _part: Part) -> Vec<Warning> {
+        vec![]
+    }
+
+    fn parse_file(input_path: &str) -> GenericResult<Self::Parsed> {
+        Self::parse(&crate::utility::input::read_input(input_path)?)
+    }
+}
+
+// Every day's answer ends up as one of these, integers are kept wide enough for any puzzle and
+// anything that isn't a number (including multi-line output) is text
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+}
+
+impl Answer {
+    pub fn line_count(&self) -> usize {
+        match self {
+            Answer::Integer(_) => 1,
+            Answer::Text(text) => text.lines().count(),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($integer:ty),*) => {
+        $(impl From<$integer> for Answer {
+            fn from(value: $integer) -> Self {
+                Answer::Integer(value as i128)
+            }
+        })*
+    };
+}
+
+integer_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+
+// u128 only becomes text when it doesn't fit
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(value) => Answer::Integer(value),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, serde::Serialize)]
+pub struct Warning {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> usize {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Part::One => "one",
+            Part::Two => "two",
+        }
+    }
+}
+
+pub struct PartRun {
+    pub part: Part,
+    pub answer: GenericResult<Answer>,
+    pub warnings: Vec<Warning>,
+    pub solve_times: Vec<Duration>,
+}
+
+pub struct DayRun {
+    pub parse_times: Vec<Duration>,
+    pub parts: Vec<PartRun>,
+}
+
+pub struct Day {
+    pub number: u16,
+    pub run: fn(&str, &[Part], usize, &DayConfig) -> GenericResult<DayRun>,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let output = f();
+    (output, start.elapsed())
+}
+
+// The outer error is a failure to parse the input, the inner ones are per part so one
+// failing part does not prevent the other from reporting.
+// Parsing and each part are repeated `repeats` times for benchmarking, stopping early on an error
+fn run<S: Solution>(input: &str, parts: &[Part], repeats: usize, config: &DayConfig) -> GenericResult<DayRun> {
+    let mut parse_times = vec![];
+    let mut parsed = None;
+    for _ in 0..repeats.max(1) {
+        let (result, time) = timed(|| S::parse_configured(input, config));
+        parsed = Some(result?);
+        parse_times.push(time);
+    }
+    let parsed = parsed.unwrap();
+
+    let parts = parts
+        .iter()
+        .map(|part| {
+            let mut solve_times = vec![];
+            let mut answer = Ok(Answer::Integer(0));
+            for _ in 0..repeats.max(1) {
+                let (result, time) = timed(|| match part {
+                    Part::One => S::part_1(&parsed),
+                    Part::Two => S::part_2(&parsed),
+                });
+                solve_times.push(time);
+                answer = result.map(Into::into);
+                if answer.is_err() {
+                    break;
+                }
+            }
+            PartRun {
+                part: *part,
+                warnings: S::warnings(&parsed, *part),
+                answer,
+                solve_times,
+            }
+        })
+        .collect();
+
+    Ok(DayRun { parse_times, parts })
+}
+
+// Add new days here, the dispatcher in main picks them up from this list
+pub const DAYS: &[Day] = &[
+    Day { number: 1, run: run::<day1::Day1> },
+    Day { number: 2, run: run::<day2::Day2> },
+    Day { number: 3, run: run::<day3::Day3> },
+    Day { number: 4, run: run::<day4::Day4> },
+    Day { number: 5, run: run::<day5::Day5> },
+    Day { number: 6, run: run::<day6::Day6> },
+    Day { number: 7, run: run::<day7::Day7> },
+];
+
+pub fn find_day(number: u16) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum RenderFormat {
+    Ansi,
+    Html,
+}
+
+pub struct Renderer {
+    pub day: u16,
+    pub render: fn(&str, RenderFormat) -> GenericResult<String>,
+}
+
+// Days that can draw their puzzle input for debugging, used by the render subcommand
+pub const RENDERERS: &[Renderer] = &[Renderer { day: 3, render: day3::render }];
+
+pub fn find_renderer(day: u16) -> Option<&'static Renderer> {
+    RENDERERS.iter().find(|renderer| renderer.day == day)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+}
+
+pub struct Explainer {
+    pub day: u16,
+    pub explain: fn(&str, Part, &DayConfig, ExplainFormat) -> GenericResult<String>,
+}
+
+// Days that can show how each line of their input contributed to an answer, used by the
+// explain subcommand
+pub const EXPLAINERS: &[Explainer] = &[Explainer { day: 1, explain: day1::explain }];
+
+pub fn find_explainer(day: u16) -> Option<&'static Explainer> {
+    EXPLAINERS.iter().find(|explainer| explainer.day == day)
+}
+
+#[test]
+fn test_answers_keep_wide_integers_and_text() {
+    assert_eq!(Answer::from(u64::MAX).to_string(), u64::MAX.to_string());
+    assert_eq!(Answer::from(u128::MAX), Answer::Text(u128::MAX.to_string()));
+    assert_eq!(Answer::from(-3i64), Answer::Integer(-3));
+    assert_eq!(Answer::from("#..\n.#.\n..#").line_count(), 3);
+}
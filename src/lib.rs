@@ -0,0 +1,4 @@
+pub mod implementations;
+pub mod runner;
+pub mod scaffold;
+pub mod utility;
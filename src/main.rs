@@ -1,27 +1,219 @@
+use std::process::ExitCode;
+
 use clap::Parser;
 
+use aoc23::implementations::{self, Day, ExplainFormat, Part, RenderFormat};
+use aoc23::runner::{self, OutputFormat, RunOptions};
+use aoc23::scaffold;
+use aoc23::utility::config::{self, DayConfig};
+use aoc23::utility::generic_error::{GenericError, GenericResult};
+use aoc23::utility::input::read_input;
+
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true,
+    after_help = "Exit codes:\n  0  every part succeeded\n  1  a solver failed\n  2  invalid arguments or unknown day\n  3  an input file could not be read\n  4  an input file could not be parsed\n  5  an answer did not match the expected one\n  6  a file could not be written"
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u16>,
+
+    /// Run every registered day and print a summary table
+    #[arg(short, long, conflicts_with_all = ["day", "input"])]
+    all: bool,
+
+    /// Only run one part of the puzzle
+    #[arg(short, long)]
+    part: Option<Part>,
+
+    #[command(flatten)]
+    day_args: DayArgs,
+
+    /// Run against test_data/dayN/example.txt and check the answers in test_data/dayN/expected.toml
+    #[arg(short, long, conflicts_with = "input")]
+    example: bool,
+
+    /// Store the answers for data/dayN.txt in data/answers.toml
+    #[arg(short, long, conflicts_with_all = ["input", "example", "config", "config_file"])]
+    record: bool,
+
+    /// Report how long parsing and solving took for each part
     #[arg(short, long)]
-    day: u16,
+    time: bool,
+
+    /// Repeat parsing and solving N times and report the min, median and max durations
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+
+    /// How to print the results, json and csv emit one record per part
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
 }
 
-mod implementations;
-mod utility;
+// Where a day reads its puzzle input from, shared by runs and every subcommand that reads one
+#[derive(clap::Args, Debug)]
+struct InputArgs {
+    /// Read the puzzle input from this path instead of data/dayN.txt, "-" reads stdin
+    #[arg(short, long)]
+    input: Option<String>,
+}
 
-pub fn main() -> utility::generic_error::GenericResult<()> {
-    let args = Args::parse();
-    let path_to_data = format!("data/day{}.txt", args.day);
-    match &args.day {
-        1 => implementations::day1::run(&path_to_data)?,
-        2 => implementations::day2::run(&path_to_data)?,
-        3 => implementations::day3::run(&path_to_data)?,
-        4 => implementations::day4::run(&path_to_data)?,
-        5 => implementations::day5::run(&path_to_data)?,
-        6 => implementations::day6::run(&path_to_data)?,
-        7 => implementations::day7::run(&path_to_data)?,
-        _ => return Err(utility::generic_error::GenericError::IOError(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown day {}", args.day)))),
+impl InputArgs {
+    // Errors from `f` are labelled with the input's path unless they already name another file
+    fn with_input<T>(&self, day: u16, f: impl FnOnce(&str) -> GenericResult<T>) -> GenericResult<T> {
+        let input_path = self.input.clone().unwrap_or_else(|| runner::default_input_path(day));
+        read_input(&input_path)
+            .and_then(|input| f(&input))
+            .map_err(|e| e.with_path(&input_path))
+    }
+}
+
+// Where a day reads its input and settings from, shared by runs and the explain subcommand
+#[derive(clap::Args, Debug)]
+struct DayArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// A setting for days that take them, e.g. part_2_vocabulary=french for day 1. Repeatable
+    #[arg(short, long = "config", value_name = "KEY=VALUE", value_parser = config::parse_setting)]
+    config: Vec<(String, String)>,
+
+    /// Read settings from a TOML file, --config settings take precedence over it
+    #[arg(long, value_name = "PATH")]
+    config_file: Option<String>,
+}
+
+impl DayArgs {
+    fn day_config(&self) -> GenericResult<DayConfig> {
+        let mut config = match &self.config_file {
+            Some(path) => DayConfig::load(path)?,
+            None => DayConfig::default(),
+        };
+        for (key, value) in self.config.iter() {
+            config.set(key, value);
+        }
+        Ok(config)
+    }
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Generate the module, registry entry and placeholder inputs for a new day
+    NewDay { day: u16 },
+    /// Draw a day's puzzle input with what the solver found in it highlighted
+    Render {
+        day: u16,
+
+        #[command(flatten)]
+        input: InputArgs,
+
+        /// ANSI colours for the terminal or a standalone HTML snippet
+        #[arg(short, long, value_enum, default_value_t = RenderFormat::Ansi)]
+        format: RenderFormat,
+    },
+    /// Show how the solver read each line of a day's puzzle input for one part
+    Explain {
+        day: u16,
+
+        #[command(flatten)]
+        day_args: DayArgs,
+
+        /// The part to explain
+        #[arg(short, long, default_value = "2")]
+        part: Part,
+
+        /// A line of text per input line, or a single JSON document
+        #[arg(short, long, value_enum, default_value_t = ExplainFormat::Text)]
+        format: ExplainFormat,
+    },
+}
+
+fn run_command(command: Command) -> GenericResult<()> {
+    match command {
+        Command::NewDay { day } => {
+            for path in scaffold::new_day(day)? {
+                println!("Wrote {}", path);
+            }
+        }
+        Command::Render { day, input, format } => {
+            let renderer = implementations::find_renderer(day)
+                .ok_or_else(|| GenericError::UsageError(format!("Day {} has no renderer", day)))?;
+            let rendered = input.with_input(day, |input| (renderer.render)(input, format))?;
+            print!("{}", rendered);
+        }
+        Command::Explain {
+            day,
+            day_args,
+            part,
+            format,
+        } => {
+            let explainer = implementations::find_explainer(day)
+                .ok_or_else(|| GenericError::UsageError(format!("Day {} has no explainer", day)))?;
+            // Built first, so a bad config file isn't reported against the puzzle input's path
+            let config = day_args.day_config()?;
+            let explained = day_args.input.with_input(day, |input| (explainer.explain)(input, part, &config, format))?;
+            print!("{}", explained);
+        }
     }
     Ok(())
 }
+
+fn run() -> GenericResult<()> {
+    let args = Args::parse();
+    if let Some(command) = args.command {
+        return run_command(command);
+    }
+
+    let options = RunOptions {
+        parts: match args.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        },
+        example: args.example,
+        record: args.record,
+        timing: args.time || args.bench.is_some(),
+        repeats: args.bench.unwrap_or(1) as usize,
+        config: args.day_args.day_config()?,
+    };
+
+    let days: Vec<&Day> = match args.day {
+        Some(day_number) => match implementations::find_day(day_number) {
+            Some(day) => vec![day],
+            None => return Err(GenericError::UsageError(format!("Unknown day {}", day_number))),
+        },
+        None => implementations::DAYS.iter().collect(),
+    };
+
+    let mut rows = runner::collect_rows(&days, args.day_args.input.input.as_deref(), &options);
+    match args.format {
+        OutputFormat::Text if args.day.is_some() && !args.example => runner::print_results(&rows, &options),
+        OutputFormat::Text => runner::print_summary(&rows, &options),
+        OutputFormat::Json => runner::print_json(&rows)?,
+        OutputFormat::Csv => runner::print_csv(&rows),
+    }
+
+    // Whatever did succeed is still recorded when some parts fail
+    if options.record {
+        runner::record_answers(&mut rows)?;
+    }
+
+    runner::check_failures(&rows)
+}
+
+pub fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e.render());
+            ExitCode::from(e.category().exit_code())
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::implementations::{Answer, Day, Part, Warning};
+use crate::utility::answers::{AnswerManifest, Answers};
+use crate::utility::config::DayConfig;
+use crate::utility::generic_error::{ErrorCategory, GenericError, GenericResult};
+use crate::utility::input::read_input;
+use crate::utility::timing::{format_duration, TimingSummary};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+pub struct RunOptions {
+    pub parts: Vec<Part>,
+    pub example: bool,
+    pub record: bool,
+    pub timing: bool,
+    pub repeats: usize,
+    pub config: DayConfig,
+}
+
+pub struct SummaryRow {
+    pub day: u16,
+    pub part: Part,
+    // Shared because a parse failure is reported against every part
+    pub result: Result<Answer, Rc<GenericError>>,
+    pub expected: Option<String>,
+    // Examples exist to be checked, so an example answer with nothing to compare against fails
+    pub requires_expected: bool,
+    pub warnings: Vec<Warning>,
+    pub parse_times: Vec<Duration>,
+    pub solve_times: Vec<Duration>,
+}
+
+impl SummaryRow {
+    pub fn passed(&self) -> bool {
+        match (&self.result, &self.expected) {
+            (Ok(answer), Some(expected)) => answer.to_string() == *expected,
+            (Ok(_), None) => !self.requires_expected,
+            (Err(_), _) => false,
+        }
+    }
+
+    pub fn unchecked(&self) -> bool {
+        self.requires_expected && self.expected.is_none() && self.result.is_ok()
+    }
+
+    pub fn failure_category(&self) -> Option<ErrorCategory> {
+        match &self.result {
+            Err(e) => Some(e.category()),
+            Ok(_) if !self.passed() => Some(ErrorCategory::AnswerMismatch),
+            Ok(_) => None,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        match (&self.result, &self.expected) {
+            (Err(e), _) => format!("failed: {}", e),
+            (Ok(_), None) if self.requires_expected => {
+                format!("UNCHECKED, no expected answer in test_data/day{}/expected.toml", self.day)
+            }
+            (Ok(_), None) => String::from("ok") + &self.warning_note(),
+            (Ok(answer), Some(expected)) if answer.to_string() == *expected => String::from("pass") + &self.warning_note(),
+            (Ok(_), Some(expected)) => format!("FAIL, expected {}", expected),
+        }
+    }
+
+    fn warning_note(&self) -> String {
+        match self.warnings.len() {
+            0 => String::new(),
+            1 => String::from(" (1 warning)"),
+            count => format!(" ({} warnings)", count),
+        }
+    }
+}
+
+const ANSWER_MANIFEST_PATH: &str = "data/answers.toml";
+
+pub fn default_input_path(day: u16) -> String {
+    format!("data/day{}.txt", day)
+}
+
+// Some days have a different example for each part, those live in example_part_N.txt
+fn example_input_path(day: &Day, part: Part) -> String {
+    let part_specific = format!("test_data/day{}/example_part_{}.txt", day.number, part.number());
+    if std::path::Path::new(&part_specific).exists() {
+        return part_specific;
+    }
+    format!("test_data/day{}/example.txt", day.number)
+}
+
+fn run_day(day: &Day, input_path: &str, parts: &[Part], options: &RunOptions) -> Vec<SummaryRow> {
+    let results = read_input(input_path).and_then(|input| (day.run)(&input, parts, options.repeats, &options.config));
+    match results.map_err(|e| Rc::new(e.with_path(input_path))) {
+        Ok(day_run) => day_run
+            .parts
+            .into_iter()
+            .map(|part_run| SummaryRow {
+                day: day.number,
+                part: part_run.part,
+                result: part_run.answer.map_err(|e| Rc::new(e.with_path(input_path))),
+                expected: None,
+                requires_expected: false,
+                warnings: part_run.warnings,
+                parse_times: day_run.parse_times.clone(),
+                solve_times: part_run.solve_times,
+            })
+            .collect(),
+        Err(e) => parts
+            .iter()
+            .map(|part| SummaryRow {
+                day: day.number,
+                part: *part,
+                result: Err(e.clone()),
+                expected: None,
+                requires_expected: false,
+                warnings: vec![],
+                parse_times: vec![],
+                solve_times: vec![],
+            })
+            .collect(),
+    }
+}
+
+fn check_answers(rows: &mut [SummaryRow], expected: Result<&Answers, &Rc<GenericError>>, requires_expected: bool) {
+    for row in rows.iter_mut() {
+        row.requires_expected = requires_expected;
+        match expected {
+            Ok(expected) => row.expected = expected.get(row.part.number()),
+            Err(e) => row.result = Err(e.clone()),
+        }
+    }
+}
+
+fn run_example(day: &Day, options: &RunOptions) -> Vec<SummaryRow> {
+    let expected = Answers::load(&format!("test_data/day{}/expected.toml", day.number)).map_err(Rc::new);
+
+    let mut rows = vec![];
+    for part in options.parts.iter() {
+        let input_path = example_input_path(day, *part);
+        rows.append(&mut run_day(day, &input_path, &[*part], options));
+    }
+    check_answers(&mut rows, expected.as_ref(), true);
+    rows
+}
+
+fn run_real(day: &Day, options: &RunOptions, manifest: &Result<AnswerManifest, Rc<GenericError>>) -> Vec<SummaryRow> {
+    let mut rows = run_day(day, &default_input_path(day.number), &options.parts, options);
+    let expected = manifest.as_ref().map(|manifest| manifest.day(day.number));
+    check_answers(&mut rows, expected.as_ref().map_err(|e| *e), false);
+    rows
+}
+
+// Recorded answers become the expected ones, so replacing an old answer isn't a mismatch
+pub fn record_answers(rows: &mut [SummaryRow]) -> GenericResult<()> {
+    let mut manifest = AnswerManifest::load(ANSWER_MANIFEST_PATH)?;
+    let mut recorded = 0;
+    for row in rows.iter() {
+        if let Ok(answer) = &row.result {
+            manifest.record(row.day, row.part.number(), &answer.to_string());
+            recorded += 1;
+        }
+    }
+    manifest.save(ANSWER_MANIFEST_PATH)?;
+
+    for row in rows.iter_mut() {
+        if let Ok(answer) = &row.result {
+            row.expected = Some(answer.to_string());
+        }
+    }
+    println!("Recorded {} answer(s) in {}", recorded, ANSWER_MANIFEST_PATH);
+    Ok(())
+}
+
+// A single run reports one duration, a benchmark reports min / median / max
+fn format_times(times: &[Duration]) -> String {
+    match TimingSummary::new(times) {
+        None => String::new(),
+        Some(summary) if times.len() == 1 => format_duration(summary.min),
+        Some(summary) => format!(
+            "{} / {} / {}",
+            format_duration(summary.min),
+            format_duration(summary.median),
+            format_duration(summary.max)
+        ),
+    }
+}
+
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .fold(header.len(), std::cmp::max)
+        })
+        .collect();
+
+    let format_row = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell))
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", format_row(headers.iter().map(|header| header.to_string()).collect()));
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<String>>()
+            .join("-+-")
+    );
+    for row in rows {
+        println!("{}", format_row(row.clone()));
+    }
+}
+
+pub fn print_summary(rows: &[SummaryRow], options: &RunOptions) {
+    let mut headers = vec!["Day", "Part", "Answer"];
+    if options.timing {
+        headers.extend(["Parse", "Solve"]);
+    }
+    headers.push("Status");
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![
+                row.day.to_string(),
+                row.part.number().to_string(),
+                match &row.result {
+                    Ok(answer) if answer.line_count() > 1 => format!("({} lines)", answer.line_count()),
+                    Ok(answer) => answer.to_string(),
+                    Err(_) => String::new(),
+                },
+            ];
+            if options.timing {
+                cells.push(format_times(&row.parse_times));
+                cells.push(format_times(&row.solve_times));
+            }
+            cells.push(row.status());
+            cells
+        })
+        .collect();
+
+    print_table(&headers, &cells);
+    if options.timing && options.repeats > 1 {
+        println!("Times are min / median / max over {} runs", options.repeats);
+    }
+}
+
+pub fn collect_rows(days: &[&Day], input_path: Option<&str>, options: &RunOptions) -> Vec<SummaryRow> {
+    let manifest = AnswerManifest::load(ANSWER_MANIFEST_PATH).map_err(Rc::new);
+    days.iter()
+        .flat_map(|day| match input_path {
+            // Answers are only known for our own input, not for anything passed in with --input
+            Some(input_path) => run_day(day, input_path, &options.parts, options),
+            None if options.example => run_example(day, options),
+            // Nor for our input read with different settings
+            None if !options.config.is_empty() => {
+                run_day(day, &default_input_path(day.number), &options.parts, options)
+            }
+            None => run_real(day, options, &manifest),
+        })
+        .collect()
+}
+
+pub fn print_results(rows: &[SummaryRow], options: &RunOptions) {
+    if let Some(row) = rows.first().filter(|_| options.timing) {
+        println!("Parse time: {}", format_times(&row.parse_times));
+    }
+
+    for row in rows {
+        let part = row.part.name();
+        match (&row.result, &row.expected) {
+            (Err(e), _) => println!("Part {} failed:\n{}", part, e.render()),
+            (Ok(answer), Some(expected)) if !row.passed() && !options.record => {
+                println!("Part {} result: {} (expected {})", part, answer, expected)
+            }
+            // Multi-line answers start on their own line so they keep their shape
+            (Ok(answer), _) if answer.line_count() > 1 => println!("Part {} result:\n{}", part, answer),
+            (Ok(answer), _) => println!("Part {} result: {}", part, answer),
+        }
+        for warning in row.warnings.iter() {
+            println!("Part {} warning: {}", part, warning);
+        }
+        if options.timing {
+            println!("Part {} time: {}", part, format_times(&row.solve_times));
+        }
+    }
+
+    if options.timing && options.repeats > 1 {
+        println!("Times are min / median / max over {} runs", options.repeats);
+    }
+}
+
+#[derive(serde::Serialize)]
+struct Record {
+    day: u16,
+    part: usize,
+    answer: Option<String>,
+    expected: Option<String>,
+    passed: bool,
+    parse_seconds: Option<f64>,
+    solve_seconds: Option<f64>,
+    error: Option<String>,
+    warnings: Vec<Warning>,
+}
+
+impl Record {
+    // Benchmarks are reduced to their median so every record has a single duration
+    fn new(row: &SummaryRow) -> Record {
+        let median_seconds = |times: &[Duration]| TimingSummary::new(times).map(|summary| summary.median.as_secs_f64());
+        Record {
+            day: row.day,
+            part: row.part.number(),
+            answer: row.result.as_ref().ok().map(|answer| answer.to_string()),
+            expected: row.expected.clone(),
+            passed: row.passed(),
+            parse_seconds: median_seconds(&row.parse_times),
+            solve_seconds: median_seconds(&row.solve_times),
+            error: row.result.as_ref().err().map(|e| e.to_string()),
+            warnings: row.warnings.clone(),
+        }
+    }
+
+    fn csv_fields(&self) -> Vec<String> {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        let seconds = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+        vec![
+            self.day.to_string(),
+            self.part.to_string(),
+            optional(&self.answer),
+            optional(&self.expected),
+            self.passed.to_string(),
+            seconds(self.parse_seconds),
+            seconds(self.solve_seconds),
+            optional(&self.error),
+            self.warnings.iter().map(|warning| warning.to_string()).collect::<Vec<String>>().join("; "),
+        ]
+    }
+}
+
+pub fn print_json(rows: &[SummaryRow]) -> GenericResult<()> {
+    let records: Vec<Record> = rows.iter().map(Record::new).collect();
+    println!("{}", serde_json::to_string_pretty(&records)?);
+    Ok(())
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn print_csv(rows: &[SummaryRow]) {
+    println!("day,part,answer,expected,passed,parse_seconds,solve_seconds,error,warnings");
+    for row in rows {
+        let fields: Vec<String> = Record::new(row).csv_fields().iter().map(|field| csv_escape(field)).collect();
+        println!("{}", fields.join(","));
+    }
+}
+
+// Reports the category of the first failing part, so the exit code reflects what went wrong there
+pub fn check_failures(rows: &[SummaryRow]) -> GenericResult<()> {
+    let categories: Vec<ErrorCategory> = rows.iter().filter_map(|row| row.failure_category()).collect();
+    match categories.first() {
+        Some(category) => Err(GenericError::PartsFailed(categories.len(), *category)),
+        None => Ok(()),
+    }
+}
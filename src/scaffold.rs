@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::utility::input::write_file;
+
+const IMPLEMENTATIONS_PATH: &str = "src/implementations";
+
+// Mirrors the layout of the existing days, {day} is replaced with the day number. The parts
+// answer 0 until they are written, so the new day doesn't break the build or the test run
+const MODULE_TEMPLATE: &str = r#"use crate::implementations::Solution;
+use crate::utility::generic_error::GenericResult;
+
+pub struct Day{day};
+
+fn part_1(_input: &str) -> GenericResult<usize> {
+    Ok(0)
+}
+
+fn part_2(_input: &str) -> GenericResult<usize> {
+    Ok(0)
+}
+
+impl Solution for Day{day} {
+    type Parsed = String;
+    type Answer = usize;
+
+    fn parse(input: &str) -> GenericResult<String> {
+        Ok(input.to_string())
+    }
+
+    fn part_1(input: &String) -> GenericResult<usize> {
+        part_1(input)
+    }
+
+    fn part_2(input: &String) -> GenericResult<usize> {
+        part_2(input)
+    }
+}
+
+#[test]
+pub fn run_test_1() -> GenericResult<()> {
+    assert_eq!(Day{day}::part_1(&Day{day}::parse_file("test_data/day{day}/example.txt")?)?, 0);
+    Ok(())
+}
+
+#[test]
+pub fn run_test_2() -> GenericResult<()> {
+    assert_eq!(Day{day}::part_2(&Day{day}::parse_file("test_data/day{day}/example.txt")?)?, 0);
+    Ok(())
+}
+"#;
+
+const EXPECTED_TEMPLATE: &str = "# part_1 = \n# part_2 = \n";
+
+pub fn render_module(day: u16) -> String {
+    MODULE_TEMPLATE.replace("{day}", &day.to_string())
+}
+
+// Adds the `pub mod` declaration and the registry entry, keeping the line endings of the file
+pub fn register_module(mod_rs: &str, day: u16) -> GenericResult<String> {
+    let newline = if mod_rs.contains("\r\n") { "\r\n" } else { "\n" };
+    // mod.rs is read like an input, so a layout we don't recognise is malformed input
+    let malformed = |message: &str| {
+        GenericError::BasicError(message.to_string()).parsing("scaffold::register_module", mod_rs, mod_rs)
+    };
+    let mut lines: Vec<String> = mod_rs.lines().map(String::from).collect();
+
+    let module_line = format!("pub mod day{};", day);
+    if lines.contains(&module_line) {
+        return Err(GenericError::UsageError(format!("day {} is already registered", day)));
+    }
+
+    let last_module = lines
+        .iter()
+        .rposition(|line| line.starts_with("pub mod day"))
+        .ok_or_else(|| malformed("could not find the day modules"))?;
+    lines.insert(last_module + 1, module_line);
+
+    let registry_start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))
+        .ok_or_else(|| malformed("could not find the DAYS registry"))?;
+    let registry_end = registry_start
+        + lines[registry_start..]
+            .iter()
+            .position(|line| line.trim() == "];")
+            .ok_or_else(|| malformed("could not find the end of the DAYS registry"))?;
+    lines.insert(
+        registry_end,
+        format!("    Day {{ number: {day}, run: run::<day{day}::Day{day}> }},"),
+    );
+
+    let mut output = lines.join(newline);
+    if mod_rs.ends_with('\n') {
+        output.push_str(newline);
+    }
+    Ok(output)
+}
+
+// Returns the paths that were created or modified
+pub fn new_day(day: u16) -> GenericResult<Vec<String>> {
+    new_day_in(Path::new("."), day)
+}
+
+// Everything is checked before anything is written, so a refused day leaves the tree as it was.
+// Inputs and examples that were already downloaded are kept rather than overwritten
+fn new_day_in(root: &Path, day: u16) -> GenericResult<Vec<String>> {
+    let mod_rs_path = format!("{}/mod.rs", IMPLEMENTATIONS_PATH);
+    let module_path = format!("{}/day{}.rs", IMPLEMENTATIONS_PATH, day);
+    let mod_rs = std::fs::read_to_string(root.join(&mod_rs_path))
+        .map_err(|e| GenericError::from(e).with_path(&mod_rs_path))?;
+    let registered = register_module(&mod_rs, day).map_err(|e| e.with_path(&mod_rs_path))?;
+    if root.join(&module_path).exists() {
+        return Err(GenericError::UsageError(format!("{} already exists", module_path)));
+    }
+
+    let files = [
+        (module_path, render_module(day)),
+        (format!("data/day{}.txt", day), String::new()),
+        (format!("test_data/day{}/example.txt", day), String::new()),
+        (format!("test_data/day{}/expected.toml", day), EXPECTED_TEMPLATE.to_string()),
+    ];
+
+    let mut created = vec![];
+    for (path, contents) in files.iter() {
+        let full_path: PathBuf = root.join(path);
+        if full_path.exists() {
+            continue;
+        }
+        write_file(&full_path, contents)?;
+        created.push(path.clone());
+    }
+
+    write_file(&root.join(&mod_rs_path), &registered)?;
+    created.push(mod_rs_path);
+    Ok(created)
+}
+
+#[test]
+fn test_render_module() {
+    let module = render_module(12);
+    assert!(module.contains("pub struct Day12;"));
+    assert!(module.contains("impl Solution for Day12 {"));
+    assert!(module.contains("\"test_data/day12/example.txt\""));
+    assert!(!module.contains("{day}"));
+}
+
+#[test]
+fn test_register_module() -> GenericResult<()> {
+    let mod_rs = "pub mod day1;\r\npub mod day2;\r\n\r\npub const DAYS: &[Day] = &[\r\n    Day { number: 1, run: run::<day1::Day1> },\r\n    Day { number: 2, run: run::<day2::Day2> },\r\n];\r\n";
+    let expected = "pub mod day1;\r\npub mod day2;\r\npub mod day3;\r\n\r\npub const DAYS: &[Day] = &[\r\n    Day { number: 1, run: run::<day1::Day1> },\r\n    Day { number: 2, run: run::<day2::Day2> },\r\n    Day { number: 3, run: run::<day3::Day3> },\r\n];\r\n";
+    assert_eq!(register_module(mod_rs, 3)?, expected);
+    assert!(register_module(expected, 3).is_err());
+    let no_registry = register_module("pub mod day1;\n", 2).unwrap_err();
+    assert_eq!(no_registry.category().exit_code(), 4);
+    Ok(())
+}
+
+#[test]
+fn test_new_day_keeps_existing_inputs() -> GenericResult<()> {
+    let root = std::env::temp_dir().join(format!("aoc23_scaffold_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join(IMPLEMENTATIONS_PATH))?;
+    std::fs::create_dir_all(root.join("data"))?;
+    let mod_rs = "pub mod day1;\n\npub const DAYS: &[Day] = &[\n    Day { number: 1, run: run::<day1::Day1> },\n];\n";
+    std::fs::write(root.join(IMPLEMENTATIONS_PATH).join("mod.rs"), mod_rs)?;
+    std::fs::write(root.join("data/day9.txt"), "downloaded")?;
+
+    let created = new_day_in(&root, 9)?;
+    assert!(!created.contains(&String::from("data/day9.txt")));
+    assert_eq!(std::fs::read_to_string(root.join("data/day9.txt"))?, "downloaded");
+    assert!(std::fs::read_to_string(root.join(IMPLEMENTATIONS_PATH).join("mod.rs"))?.contains("pub mod day9;"));
+    assert!(root.join("test_data/day9/expected.toml").exists());
+
+    // A module file with no registry entry is refused before mod.rs is touched
+    std::fs::write(root.join(IMPLEMENTATIONS_PATH).join("mod.rs"), mod_rs)?;
+    let refused = new_day_in(&root, 9);
+    assert!(matches!(refused, Err(GenericError::UsageError(_))));
+    assert_eq!(std::fs::read_to_string(root.join(IMPLEMENTATIONS_PATH).join("mod.rs"))?, mod_rs);
+
+    std::fs::remove_dir_all(&root)?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::utility::input::write_file;
+use crate::utility::toml_file::parse_table;
+
+// A missing file is not an error, it just means there is nothing to check against
+fn load_table(path: &str) -> GenericResult<toml::Table> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse_table(&contents, "answers").map_err(|e| e.with_path(path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(toml::Table::new()),
+        Err(e) => Err(GenericError::from(e).with_path(path)),
+    }
+}
+
+// Known answers are stored as `part_1 = ...` and `part_2 = ...`, either as integers or strings
+pub struct Answers {
+    table: toml::Table,
+}
+
+impl Answers {
+    pub fn load(path: &str) -> GenericResult<Answers> {
+        Ok(Answers {
+            table: load_table(path)?,
+        })
+    }
+
+    pub fn get(&self, part: usize) -> Option<String> {
+        match self.table.get(&format!("part_{}", part))? {
+            toml::Value::String(answer) => Some(answer.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+// The same format as Answers, with one `[dayN]` table per day
+pub struct AnswerManifest {
+    table: toml::Table,
+}
+
+impl AnswerManifest {
+    pub fn load(path: &str) -> GenericResult<AnswerManifest> {
+        Ok(AnswerManifest {
+            table: load_table(path)?,
+        })
+    }
+
+    pub fn day(&self, day: u16) -> Answers {
+        let table = match self.table.get(&format!("day{}", day)) {
+            Some(toml::Value::Table(table)) => table.clone(),
+            _ => toml::Table::new(),
+        };
+        Answers { table }
+    }
+
+    pub fn record(&mut self, day: u16, part: usize, answer: &str) {
+        let day_table = self
+            .table
+            .entry(format!("day{}", day))
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+
+        // Keep numbers as TOML integers so the file stays readable, anything else is a string
+        let value = match answer.parse::<i64>() {
+            Ok(number) => toml::Value::Integer(number),
+            Err(_) => toml::Value::String(answer.to_string()),
+        };
+
+        if let toml::Value::Table(day_table) = day_table {
+            day_table.insert(format!("part_{}", part), value);
+        }
+    }
+
+    pub fn save(&self, path: &str) -> GenericResult<()> {
+        write_file(std::path::Path::new(path), &self.table.to_string())
+    }
+}
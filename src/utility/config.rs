@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::utility::toml_file::{error_at_key, parse_table};
+
+// Settings for days that can be tuned beyond their puzzle input, given on the command line as
+// `--config key=value` or in a TOML file. Days ignore keys they don't know about
+#[derive(Clone, Default, Debug)]
+pub struct DayConfig {
+    values: BTreeMap<String, String>,
+}
+
+impl DayConfig {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // Every `prefix.name` key, as (name, value)
+    pub fn section<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.values.iter().filter_map(move |(key, value)| {
+            let name = key.strip_prefix(prefix)?.strip_prefix('.')?;
+            Some((name, value.as_str()))
+        })
+    }
+
+    // Tables are flattened into dotted keys, so `[bag] red = 12` is the setting `bag.red=12`
+    pub fn parse(contents: &str) -> GenericResult<DayConfig> {
+        let table = parse_table(contents, "config")?;
+        let mut config = DayConfig::default();
+        config.flatten(&[], &table, contents)?;
+        Ok(config)
+    }
+
+    pub fn load(path: &str) -> GenericResult<DayConfig> {
+        std::fs::read_to_string(path)
+            .map_err(GenericError::from)
+            .and_then(|contents| DayConfig::parse(&contents))
+            .map_err(|e| e.with_path(path))
+    }
+
+    fn flatten(&mut self, prefix: &[&str], table: &toml::Table, contents: &str) -> GenericResult<()> {
+        for (name, value) in table.iter() {
+            let path = [prefix, &[name.as_str()]].concat();
+            let key = path.join(".");
+            match value {
+                toml::Value::Table(table) => self.flatten(&path, table, contents)?,
+                toml::Value::String(value) => self.set(&key, value),
+                toml::Value::Array(_) => {
+                    let error = GenericError::BasicError(format!("\"{}\" should be a single value, not a list", key));
+                    return Err(error_at_key(error, "config", contents, &path));
+                }
+                other => self.set(&key, &other.to_string()),
+            }
+        }
+        Ok(())
+    }
+}
+
+// For clap, so a malformed setting is reported as a usage error before anything runs
+pub fn parse_setting(setting: &str) -> Result<(String, String), String> {
+    match setting.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("expected key=value, got \"{}\"", setting)),
+    }
+}
+
+#[test]
+fn test_parse_setting() -> Result<(), String> {
+    assert_eq!(parse_setting("vocabulary = french")?, (String::from("vocabulary"), String::from("french")));
+    assert_eq!(parse_setting("path=a=b")?, (String::from("path"), String::from("a=b")));
+    assert!(parse_setting("vocabulary").is_err());
+    assert!(parse_setting("=french").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_config_files() -> GenericResult<()> {
+    let config = DayConfig::parse("policy = \"lenient\"\n[bag]\nred = 20\nyellow = 5\n[other]\nred = 1")?;
+    assert_eq!(config.get("policy"), Some("lenient"));
+    assert_eq!(config.get("bag.red"), Some("20"));
+    assert_eq!(config.section("bag").collect::<Vec<_>>(), vec![("red", "20"), ("yellow", "5")]);
+    assert!(DayConfig::parse("bag = [1, 2]").is_err());
+    assert!(DayConfig::parse("bag =").is_err());
+    Ok(())
+}
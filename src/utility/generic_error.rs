@@ -3,13 +3,184 @@ use std::fmt::{Display, Formatter, Error as FmtError};
 use std::num::{ParseIntError, ParseFloatError};
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum GenericError {
     BasicError(String),
     ParseIntError(ParseIntError),
     ParseFloatError(ParseFloatError),
-    IOError(std::io::Error),
+    IOError(std::io::Error, Option<String>), // the path, when known
+    WriteError(std::io::Error, String),
     SscanfError(sscanf::Error),
     StrumParseError(strum::ParseError),
+    TomlError(toml::de::Error),
+    JsonError(serde_json::Error),
+    ParseFailure(Box<ParseFailure>),
+    UsageError(String),
+    PartsFailed(usize, ErrorCategory),
+}
+
+// The CLI exits with the code of the error's category, see the help text in main
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    SolverFailure,
+    Usage,
+    MissingInput,
+    MalformedInput,
+    AnswerMismatch,
+    WriteFailure,
+}
+
+impl ErrorCategory {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::SolverFailure => 1,
+            Self::Usage => 2, // Matches clap's own exit code for bad arguments
+            Self::MissingInput => 3,
+            Self::MalformedInput => 4,
+            Self::AnswerMismatch => 5,
+            Self::WriteFailure => 6,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SourceLocation {
+    pub line_number: usize, // 1-based
+    pub column: usize,      // 1-based, in characters
+    pub line: String,
+    pub length: usize, // in characters, at least 1
+}
+
+#[derive(Debug)]
+pub struct ParseFailure {
+    pub source: GenericError,
+    pub parsers: Vec<&'static str>, // innermost parser first
+    pub path: Option<String>,
+    pub location: Option<SourceLocation>,
+    // Address and length of the offending text. Only used to find it again inside an enclosing
+    // input, so the location always ends up relative to the outermost parser's input
+    fragment: (usize, usize),
+}
+
+impl SourceLocation {
+    // Returns None if `fragment` is not a slice of `input`
+    fn find(input: &str, fragment: (usize, usize)) -> Option<SourceLocation> {
+        let input_start = input.as_ptr() as usize;
+        let offset = fragment.0.checked_sub(input_start)?;
+        if offset + fragment.1 > input.len() || !input.is_char_boundary(offset) {
+            return None;
+        }
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[offset..].find('\n').map_or(input.len(), |index| offset + index);
+        let line = input[line_start..line_end].trim_end_matches('\r');
+        let fragment_end = (offset + fragment.1).min(line_start + line.len()).max(offset);
+
+        Some(SourceLocation {
+            line_number: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            line: line.to_string(),
+            length: input[offset..fragment_end].chars().count().max(1),
+        })
+    }
+}
+
+impl GenericError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::BasicError(_) | Self::JsonError(_) => ErrorCategory::SolverFailure,
+            Self::IOError(..) => ErrorCategory::MissingInput,
+            Self::WriteError(..) => ErrorCategory::WriteFailure,
+            Self::ParseIntError(_)
+            | Self::ParseFloatError(_)
+            | Self::SscanfError(_)
+            | Self::StrumParseError(_)
+            | Self::TomlError(_)
+            | Self::ParseFailure(_) => ErrorCategory::MalformedInput,
+            Self::UsageError(_) => ErrorCategory::Usage,
+            Self::PartsFailed(_, category) => *category,
+        }
+    }
+
+    // Records that `parser` failed on `fragment`, which must be a slice of `input`. Calling this
+    // again from an enclosing parser extends the context chain and re-locates the original
+    // fragment inside the larger input
+    pub fn parsing(self, parser: &'static str, input: &str, fragment: &str) -> GenericError {
+        match self {
+            GenericError::ParseFailure(mut failure) => {
+                if failure.parsers.last() != Some(&parser) {
+                    failure.parsers.push(parser);
+                }
+                if let Some(location) = SourceLocation::find(input, failure.fragment) {
+                    failure.location = Some(location);
+                }
+                GenericError::ParseFailure(failure)
+            }
+            source => {
+                let fragment = (fragment.as_ptr() as usize, fragment.len());
+                GenericError::ParseFailure(Box::new(ParseFailure {
+                    source,
+                    parsers: vec![parser],
+                    path: None,
+                    location: SourceLocation::find(input, fragment),
+                    fragment,
+                }))
+            }
+        }
+    }
+
+    // The innermost path wins, so an error from a file the input refers to (a vocabulary, say)
+    // is not relabelled with the path of the input itself
+    pub fn with_path(self, path: &str) -> GenericError {
+        match self {
+            GenericError::ParseFailure(mut failure) => {
+                failure.path.get_or_insert_with(|| path.to_string());
+                GenericError::ParseFailure(failure)
+            }
+            GenericError::IOError(e, None) => GenericError::IOError(e, Some(path.to_string())),
+            other => other,
+        }
+    }
+
+    // Multi-line diagnostic with a caret under the offending text, when the location is known
+    pub fn render(&self) -> String {
+        let failure = match self {
+            GenericError::ParseFailure(failure) => failure,
+            other => return format!("error: {}", other),
+        };
+
+        let mut output = format!("error: {}\n", failure.source);
+        let path = failure.path.as_deref().unwrap_or("<input>");
+        match &failure.location {
+            Some(location) => {
+                let gutter = " ".repeat(location.line_number.to_string().len());
+                output += &format!("{}--> {}:{}:{}\n", gutter, path, location.line_number, location.column);
+                output += &format!("{} |\n", gutter);
+                output += &format!("{} | {}\n", location.line_number, location.line);
+                output += &format!(
+                    "{} | {}{}\n",
+                    gutter,
+                    " ".repeat(location.column - 1),
+                    "^".repeat(location.length)
+                );
+            }
+            None => output += &format!(" --> {}\n", path),
+        }
+        for parser in failure.parsers.iter() {
+            output += &format!("  = while parsing {}\n", parser);
+        }
+        output.trim_end().to_string()
+    }
+}
+
+pub trait ParseContext<T> {
+    fn parsing(self, parser: &'static str, input: &str, fragment: &str) -> GenericResult<T>;
+}
+
+impl<T, E: Into<GenericError>> ParseContext<T> for Result<T, E> {
+    fn parsing(self, parser: &'static str, input: &str, fragment: &str) -> GenericResult<T> {
+        self.map_err(|e| e.into().parsing(parser, input, fragment))
+    }
 }
 
 impl From<ParseIntError> for GenericError {
@@ -26,7 +197,7 @@ impl From<ParseFloatError> for GenericError {
 
 impl From<std::io::Error> for GenericError {
     fn from(e: std::io::Error) -> Self {
-        Self::IOError(e)
+        Self::IOError(e, None)
     }
 }
 
@@ -42,6 +213,18 @@ impl From<strum::ParseError> for GenericError {
     }
 }
 
+impl From<toml::de::Error> for GenericError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::TomlError(e)
+    }
+}
+
+impl From<serde_json::Error> for GenericError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::JsonError(e)
+    }
+}
+
 impl Error for GenericError {}
 
 impl Display for GenericError {
@@ -50,11 +233,89 @@ impl Display for GenericError {
             Self::BasicError(e) => write!(f, "basic error: {}", e),
             Self::ParseIntError(e) => write!(f, "invalid integer: {}", e),
             Self::ParseFloatError(e) => write!(f, "invalid float: {}", e),
-            Self::IOError(e) => write!(f, "io error: {}", e),
+            Self::IOError(e, None) => write!(f, "io error: {}", e),
+            Self::IOError(e, Some(path)) => write!(f, "io error: {}: {}", path, e),
+            Self::WriteError(e, path) => write!(f, "could not write {}: {}", path, e),
             Self::SscanfError(e) => write!(f, "sscanf error: {}", e),
             Self::StrumParseError(e) => write!(f, "strum parse error: {}", e),
+            Self::TomlError(e) => write!(f, "toml error: {}", e),
+            Self::JsonError(e) => write!(f, "json error: {}", e),
+            Self::UsageError(e) => write!(f, "usage error: {}", e),
+            Self::PartsFailed(count, _) => write!(f, "{} part(s) failed", count),
+            Self::ParseFailure(failure) => {
+                write!(f, "{}", failure.parsers[0])?;
+                if let Some(path) = &failure.path {
+                    write!(f, " in {}", path)?;
+                }
+                if let Some(location) = &failure.location {
+                    write!(f, " at line {}, column {}", location.line_number, location.column)?;
+                }
+                write!(f, ": {}", failure.source)
+            }
+        }
+    }
+}
+
+pub type GenericResult<T> = Result<T, GenericError>;
+
+#[test]
+fn test_parse_failure_relocates_in_enclosing_input() {
+    let input = "seeds: 1 2\n\nmap:\n50 98 x2\n";
+    let section = &input[12..];
+    let line = section.lines().nth(1).unwrap();
+    let fragment = &line[6..];
+
+    let error = fragment
+        .parse::<usize>()
+        .parsing("inner", line, fragment)
+        .parsing("outer", section, section)
+        .parsing("outer", input, input)
+        .unwrap_err()
+        .with_path("example.txt");
+
+    match &error {
+        GenericError::ParseFailure(failure) => {
+            assert_eq!(failure.parsers, vec!["inner", "outer"]);
+            assert_eq!(
+                failure.location,
+                Some(SourceLocation {
+                    line_number: 4,
+                    column: 7,
+                    line: String::from("50 98 x2"),
+                    length: 2,
+                })
+            );
         }
+        _ => panic!("expected a parse failure"),
     }
+
+    assert_eq!(
+        error.render(),
+        "error: invalid integer: invalid digit found in string\n --> example.txt:4:7\n  |\n4 | 50 98 x2\n  |       ^^\n  = while parsing inner\n  = while parsing outer"
+    );
 }
 
-pub type GenericResult<T> = Result<T, GenericError>;
\ No newline at end of file
+#[test]
+fn test_categories_survive_parse_context() {
+    let input = "seeds: x";
+    let error = GenericError::from("x".parse::<usize>().unwrap_err()).parsing("seeds", input, &input[7..]);
+    assert_eq!(error.category(), ErrorCategory::MalformedInput);
+    assert_eq!(error.with_path("data/day5.txt").category().exit_code(), 4);
+    assert_eq!(GenericError::UsageError(String::from("Unknown day 99")).category().exit_code(), 2);
+    assert_eq!(GenericError::PartsFailed(2, ErrorCategory::AnswerMismatch).category().exit_code(), 5);
+}
+
+#[test]
+fn test_with_path_keeps_the_innermost_path() {
+    let input = "diez = 10";
+    let error = GenericError::BasicError(String::from("not a digit"))
+        .parsing("vocabulary", input, input)
+        .with_path("vocabulary.toml")
+        .with_path("data/day1.txt");
+    assert!(error.render().contains("--> vocabulary.toml:1:1"), "{}", error.render());
+
+    let missing = std::fs::read_to_string("no/such/file.txt").map_err(GenericError::from).unwrap_err();
+    let missing = missing.with_path("no/such/file.txt").with_path("data/day1.txt");
+    assert!(missing.to_string().starts_with("io error: no/such/file.txt: "), "{}", missing);
+    assert_eq!(missing.category().exit_code(), 3);
+}
@@ -0,0 +1,218 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::utility::generic_error::{GenericError, GenericResult};
+use crate::utility::input::lines;
+
+// (row, col), with (0, 0) in the top left
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// A rectangular grid stored row by row
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, cells: Vec<T>) -> GenericResult<Grid<T>> {
+        // Nothing but an empty grid is a multiple of zero
+        if !cells.len().is_multiple_of(width) {
+            return Err(GenericError::BasicError(format!(
+                "{} cells do not make rows of width {}",
+                cells.len(),
+                width
+            )));
+        }
+
+        let height = cells.len().checked_div(width).unwrap_or_default();
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Position) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position).then(|| &self.cells[position.0 * self.width + position.1])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if !self.contains(position) {
+            return None;
+        }
+        Some(&mut self.cells[position.0 * self.width + position.1])
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    // Columns aren't contiguous, so unlike rows they come back as an iterator
+    pub fn column(&self, col: usize) -> Option<impl Iterator<Item = &T>> {
+        (col < self.width).then(|| self.cells.iter().skip(col).step_by(self.width))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on zero, an empty grid has no rows either way
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    fn offset_positions(&self, (row, col): Position, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = Position> + '_ {
+        offsets.iter().filter_map(move |(row_offset, col_offset)| {
+            let position = (row.checked_add_signed(*row_offset)?, col.checked_add_signed(*col_offset)?);
+            self.contains(position).then_some(position)
+        })
+    }
+
+    // Up, left, right and down, skipping anything off the edge of the grid
+    pub fn neighbours_4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offset_positions(position, &OFFSETS_4)
+    }
+
+    // Same as neighbours_4 but including the diagonals
+    pub fn neighbours_8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offset_positions(position, &OFFSETS_8)
+    }
+
+    pub fn find_all<'a>(&'a self, predicate: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = Position> + 'a {
+        self.iter().filter(move |(_, value)| predicate(value)).map(|(position, _)| position)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        match self.get(position) {
+            Some(value) => value,
+            None => panic!("{:?} is outside a {}x{} grid", position, self.height, self.width),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        match self.get_mut(position) {
+            Some(value) => value,
+            None => panic!("{:?} is outside a {}x{} grid", position, height, width),
+        }
+    }
+}
+
+// One line per row, every row must be as wide as the first
+impl FromStr for Grid<char> {
+    type Err = GenericError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut cells = vec![];
+
+        for line in lines(input) {
+            let line_width = line.chars().count();
+            if *width.get_or_insert(line_width) != line_width {
+                let error = GenericError::BasicError(format!("expected every row to be {} wide", width.unwrap_or_default()));
+                return Err(error.parsing("utility::Grid", input, line));
+            }
+            cells.extend(line.chars());
+        }
+
+        Grid::new(width.unwrap_or_default(), cells)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse_and_display_round_trip() -> GenericResult<()> {
+    let grid: Grid<char> = "ab.\n.cd".parse()?;
+    assert_eq!((grid.height(), grid.width()), (2, 3));
+    assert_eq!(grid[(1, 2)], 'd');
+    assert_eq!(grid.get((2, 0)), None);
+    assert_eq!(grid.to_string(), "ab.\n.cd");
+    assert!("abc\nde".parse::<Grid<char>>().is_err());
+    assert_eq!("".parse::<Grid<char>>()?.positions().count(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_rows_and_columns() -> GenericResult<()> {
+    let grid = Grid::new(3, vec![1, 2, 3, 4, 5, 6])?;
+    assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+    assert_eq!(grid.row(2), None);
+    assert_eq!(grid.column(1).map(|column| column.copied().collect::<Vec<i32>>()), Some(vec![2, 5]));
+    assert!(grid.column(3).is_none());
+    assert!(Grid::new(4, vec![1, 2, 3, 4, 5, 6]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_neighbours_stay_in_bounds() {
+    let grid = Grid::filled(3, 3, 0);
+    assert_eq!(grid.neighbours_4((0, 0)).collect::<Vec<Position>>(), vec![(0, 1), (1, 0)]);
+    assert_eq!(grid.neighbours_8((0, 0)).collect::<Vec<Position>>(), vec![(0, 1), (1, 0), (1, 1)]);
+    assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+    assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+    assert_eq!(grid.neighbours_8((2, 2)).collect::<Vec<Position>>(), vec![(1, 1), (1, 2), (2, 1)]);
+}
+
+#[test]
+fn test_find_all() -> GenericResult<()> {
+    let grid: Grid<char> = "#.\n.#".parse()?;
+    assert_eq!(grid.find_all(|c| *c == '#').collect::<Vec<Position>>(), vec![(0, 0), (1, 1)]);
+    Ok(())
+}
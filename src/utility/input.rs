@@ -0,0 +1,109 @@
+use std::io::Read;
+use std::str::FromStr;
+
+use crate::utility::generic_error::{GenericError, GenericResult, ParseContext};
+
+// A path of "-" reads the puzzle input from stdin instead of a file
+pub fn read_input(path: &str) -> GenericResult<String> {
+    let contents = if path == "-" {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        contents
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    Ok(normalise(&contents))
+}
+
+// The counterpart for files the tool writes, creating any missing directories. Failures are
+// write errors rather than missing input, so they exit with their own code
+pub fn write_file(path: &std::path::Path, contents: &str) -> GenericResult<()> {
+    let write_error = |e| GenericError::WriteError(e, path.display().to_string());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    std::fs::write(path, contents).map_err(write_error)
+}
+
+// Inputs saved on Windows or by some editors come with a BOM, CRLF line endings and trailing
+// whitespace. After this every line ends in a bare '\n' except the last, which has none
+pub fn normalise(contents: &str) -> String {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    contents
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+// Lines with any trailing whitespace removed, so they also work on input that wasn't normalised
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().map(str::trim_end)
+}
+
+// Blocks of lines separated by one or more blank lines. Each section is a slice of `input`, so
+// parse errors inside it are still located relative to the whole puzzle
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut sections = vec![];
+    let mut current: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end();
+        if content.is_empty() {
+            if let Some((start, end)) = current.take() {
+                sections.push(&input[start..end]);
+            }
+        } else {
+            let start = current.map_or(offset, |(start, _)| start);
+            current = Some((start, offset + content.len()));
+        }
+        offset += line.len();
+    }
+
+    if let Some((start, end)) = current {
+        sections.push(&input[start..end]);
+    }
+    sections.into_iter()
+}
+
+// Every whitespace separated word of `text` parsed as a number
+pub fn numbers<T>(text: &str) -> impl Iterator<Item = GenericResult<T>> + '_
+where
+    T: FromStr,
+    T::Err: Into<GenericError>,
+{
+    text.split_whitespace()
+        .map(move |word| word.parse::<T>().parsing("input::numbers", text, word))
+}
+
+#[test]
+fn test_normalise() {
+    assert_eq!(normalise("\u{feff}a b  \r\nc\r\n\r\n"), "a b\nc");
+    assert_eq!(normalise("a\n\nb\n"), "a\n\nb");
+    assert_eq!(normalise(""), "");
+}
+
+#[test]
+fn test_sections() {
+    let input = "a\nb\n \n\nc\r\n\r\nd\n";
+    assert_eq!(sections(input).collect::<Vec<&str>>(), vec!["a\nb", "c", "d"]);
+    assert_eq!(sections("").count(), 0);
+}
+
+#[test]
+fn test_numbers() -> GenericResult<()> {
+    assert_eq!(numbers::<u32>(" 41 48  83 ").collect::<GenericResult<Vec<u32>>>()?, vec![41, 48, 83]);
+    assert!(numbers::<u32>("1 x 3").collect::<GenericResult<Vec<u32>>>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_write_failures_have_their_own_category() {
+    let error = write_file(std::path::Path::new("Cargo.toml/answers.toml"), "").unwrap_err();
+    assert_eq!(error.category().exit_code(), 6);
+    assert!(error.to_string().starts_with("could not write Cargo.toml/answers.toml: "), "{}", error);
+}
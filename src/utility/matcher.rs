@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+
+// A match of one of the patterns covering the bytes start..end of the searched text
+#[derive(PartialEq, Eq, Debug)]
+pub struct Match<'a, V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: &'a V,
+}
+
+// Only a reference to the value is held, so this is copyable whatever V is
+impl<V> Clone for Match<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Match<'_, V> {}
+
+// Aho-Corasick automaton that finds every occurrence of every pattern, including overlapping
+// ones, in a single pass over the text. The goto and failure links are folded into a full
+// byte transition table up front so each byte of the text is one lookup
+pub struct Matcher<V> {
+    transitions: Vec<[usize; 256]>,
+    // Indices into `patterns` of everything that ends at this state, longest first
+    outputs: Vec<Vec<usize>>,
+    patterns: Vec<(usize, V)>,
+}
+
+impl<V> Matcher<V> {
+    // Empty patterns would match everywhere, so they are ignored
+    pub fn new<P: AsRef<str>>(patterns: impl IntoIterator<Item = (P, V)>) -> Matcher<V> {
+        let mut matcher = Matcher {
+            transitions: vec![[0; 256]],
+            outputs: vec![vec![]],
+            patterns: vec![],
+        };
+        // 0 doubles as "no transition yet" while building the trie, which is safe because
+        // nothing ever transitions back into the root while building
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref().as_bytes();
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut state = 0;
+            for byte in pattern {
+                if matcher.transitions[state][*byte as usize] == 0 {
+                    matcher.transitions.push([0; 256]);
+                    matcher.outputs.push(vec![]);
+                    matcher.transitions[state][*byte as usize] = matcher.transitions.len() - 1;
+                }
+                state = matcher.transitions[state][*byte as usize];
+            }
+            matcher.outputs[state].push(matcher.patterns.len());
+            matcher.patterns.push((pattern.len(), value));
+        }
+
+        matcher.build_failure_links();
+        matcher
+    }
+
+    fn build_failure_links(&mut self) {
+        let mut failure = vec![0; self.transitions.len()];
+        let mut queue: VecDeque<usize> = self.transitions[0].iter().copied().filter(|state| *state != 0).collect();
+
+        // Breadth first, so a state's failure target is always finished before the state itself
+        while let Some(state) = queue.pop_front() {
+            let inherited = self.outputs[failure[state]].clone();
+            self.outputs[state].extend(inherited);
+
+            for byte in 0..256 {
+                let next = self.transitions[state][byte];
+                let fallback = self.transitions[failure[state]][byte];
+                if next == 0 {
+                    self.transitions[state][byte] = fallback;
+                } else {
+                    failure[next] = fallback;
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    // Every match in order of where it ends, with longer matches first when several end together
+    pub fn find_overlapping<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match<'a, V>> + 'a {
+        let mut state = 0;
+        text.bytes().enumerate().flat_map(move |(index, byte)| {
+            state = self.transitions[state][byte as usize];
+            self.outputs[state].iter().map(move |pattern| {
+                let (length, value) = &self.patterns[*pattern];
+                Match {
+                    start: index + 1 - length,
+                    end: index + 1,
+                    value,
+                }
+            })
+        })
+    }
+
+    // The leftmost and rightmost matches. When several start (or end) at the same place the
+    // longest wins, so "VIII" is one match from either side rather than "V" and "I"
+    pub fn first_and_last<'a>(&'a self, text: &'a str) -> Option<(Match<'a, V>, Match<'a, V>)> {
+        self.find_overlapping(text).fold(None, |found, current| match found {
+            None => Some((current, current)),
+            Some((first, last)) => {
+                let first_key = |found: &Match<V>| (found.start, usize::MAX - found.end);
+                let last_key = |found: &Match<V>| (found.end, usize::MAX - found.start);
+                let first = if first_key(&current) < first_key(&first) { current } else { first };
+                let last = if last_key(&current) > last_key(&last) { current } else { last };
+                Some((first, last))
+            }
+        })
+    }
+}
+
+#[test]
+fn test_overlapping_matches() {
+    let matcher = Matcher::new([("eight", 8), ("two", 2), ("one", 1)]);
+    let found: Vec<(usize, usize, u32)> = matcher
+        .find_overlapping("eightwone")
+        .map(|found| (found.start, found.end, *found.value))
+        .collect();
+    assert_eq!(found, vec![(0, 5, 8), (4, 7, 2), (6, 9, 1)]);
+}
+
+#[test]
+fn test_patterns_inside_other_patterns() {
+    let matcher = Matcher::new([("he", "he"), ("she", "she"), ("hers", "hers"), ("his", "his")]);
+    let found: Vec<&str> = matcher.find_overlapping("ushers").map(|found| *found.value).collect();
+    assert_eq!(found, vec!["she", "he", "hers"]);
+}
+
+#[test]
+fn test_first_and_last() {
+    let matcher = Matcher::new([("I", 1), ("IV", 4), ("V", 5)]);
+    let (first, last) = matcher.first_and_last("xIVx").unwrap();
+    assert_eq!((*first.value, *last.value), (4, 4));
+    let (first, last) = matcher.first_and_last("VIV").unwrap();
+    assert_eq!((*first.value, *last.value), (5, 4));
+    let (first, last) = matcher.first_and_last("xVIx").unwrap();
+    assert_eq!((*first.value, *last.value), (5, 1));
+    assert!(matcher.first_and_last("xyz").is_none());
+    assert_eq!(Matcher::new([("", 0)]).pattern_count(), 0);
+}
@@ -1 +1,8 @@
-pub mod generic_error;
\ No newline at end of file
+pub mod answers;
+pub mod config;
+pub mod generic_error;
+pub mod grid;
+pub mod input;
+pub mod matcher;
+pub mod timing;
+pub mod toml_file;
\ No newline at end of file
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+pub struct TimingSummary {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl TimingSummary {
+    pub fn new(times: &[Duration]) -> Option<TimingSummary> {
+        let mut sorted = times.to_vec();
+        sorted.sort();
+        Some(TimingSummary {
+            min: *sorted.first()?,
+            median: sorted[sorted.len() / 2],
+            max: *sorted.last()?,
+        })
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds >= 1.0 {
+        format!("{:.2}s", seconds)
+    } else if seconds >= 1e-3 {
+        format!("{:.2}ms", seconds * 1e3)
+    } else {
+        format!("{:.2}µs", seconds * 1e6)
+    }
+}
+
+#[test]
+fn test_timing_summary() {
+    let times: Vec<Duration> = [5, 1, 3, 4, 2].into_iter().map(Duration::from_millis).collect();
+    let summary = TimingSummary::new(&times).unwrap();
+    assert_eq!(summary.min, Duration::from_millis(1));
+    assert_eq!(summary.median, Duration::from_millis(3));
+    assert_eq!(summary.max, Duration::from_millis(5));
+    assert!(TimingSummary::new(&[]).is_none());
+}
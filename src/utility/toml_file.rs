@@ -0,0 +1,49 @@
+use toml::de::{DeTable, DeValue};
+
+use crate::utility::generic_error::{GenericError, GenericResult};
+
+// Syntax errors are located at the span toml reports, or the whole file when there is none
+pub fn parse_table(contents: &str, parser: &'static str) -> GenericResult<toml::Table> {
+    contents.parse().map_err(|e: toml::de::Error| {
+        let fragment = match e.span() {
+            Some(span) => &contents[span],
+            None => contents,
+        };
+        GenericError::from(e).parsing(parser, contents, fragment)
+    })
+}
+
+// Locates `error` at the key found by following `key` through nested tables, e.g. ["bag", "red"]
+// for `red` under `[bag]`. Falls back to the whole file if the key can't be found
+pub fn error_at_key(error: GenericError, parser: &'static str, contents: &str, key: &[&str]) -> GenericError {
+    let fragment = match DeTable::parse(contents) {
+        Ok(table) => key_span(table.get_ref(), key).map_or(contents, |span| &contents[span]),
+        Err(_) => contents,
+    };
+    error.parsing(parser, contents, fragment)
+}
+
+fn key_span(table: &DeTable, key: &[&str]) -> Option<std::ops::Range<usize>> {
+    let (name, rest) = key.split_first()?;
+    let (found, value) = table.iter().find(|(found, _)| found.get_ref() == name)?;
+    match (rest, value.get_ref()) {
+        ([], _) => Some(found.span()),
+        (rest, DeValue::Table(inner)) => key_span(inner, rest),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_syntax_errors_are_located() {
+    let error = parse_table("a = 1\nb =", "test").unwrap_err();
+    assert!(error.render().contains("--> <input>:2:"), "{}", error.render());
+}
+
+#[test]
+fn test_errors_at_keys() {
+    let contents = "unos = 1\nuno = 10\n[bag]\nuno = 2";
+    let at = |key: &[&str]| error_at_key(GenericError::BasicError(String::from("bad")), "test", contents, key).render();
+    assert!(at(&["uno"]).contains("--> <input>:2:1"), "{}", at(&["uno"]));
+    assert!(at(&["bag", "uno"]).contains("--> <input>:4:1"), "{}", at(&["bag", "uno"]));
+    assert!(at(&["missing"]).contains("--> <input>:1:1"), "{}", at(&["missing"]));
+}
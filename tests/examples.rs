@@ -0,0 +1,62 @@
+use aoc23::implementations::{self, Answer, Day, Part};
+use aoc23::runner::{self, RunOptions, SummaryRow};
+use aoc23::utility::config::DayConfig;
+
+#[test]
+fn test_every_day_passes_its_examples() {
+    let days: Vec<&Day> = implementations::DAYS.iter().collect();
+    let options = RunOptions {
+        parts: Part::ALL.to_vec(),
+        example: true,
+        record: false,
+        timing: false,
+        repeats: 1,
+        config: DayConfig::default(),
+    };
+
+    let rows = runner::collect_rows(&days, None, &options);
+    assert_eq!(rows.len(), days.len() * Part::ALL.len());
+    // A freshly scaffolded day has no expected answers yet, the CLI reports those as unchecked
+    for row in rows.iter().filter(|row| !row.unchecked()) {
+        assert!(row.passed(), "day {} part {}: {}", row.day, row.part.number(), row.status());
+    }
+}
+
+#[test]
+fn test_examples_without_expected_answers_fail() {
+    let row = |requires_expected: bool, expected: Option<&str>| SummaryRow {
+        day: 8,
+        part: Part::One,
+        result: Ok(Answer::from(0)),
+        expected: expected.map(String::from),
+        requires_expected,
+        warnings: vec![],
+        parse_times: vec![],
+        solve_times: vec![],
+    };
+
+    assert!(row(false, None).passed());
+    assert!(row(true, Some("0")).passed());
+    let unchecked = row(true, None);
+    assert!(!unchecked.passed() && unchecked.unchecked());
+    assert!(unchecked.status().starts_with("UNCHECKED"));
+    assert_eq!(runner::check_failures(&[unchecked]).unwrap_err().category().exit_code(), 5);
+}
+
+#[test]
+fn test_runs_with_settings_are_not_checked_against_recorded_answers() {
+    let mut config = DayConfig::default();
+    config.set("bag.red", "1");
+    let options = RunOptions {
+        parts: vec![Part::One],
+        example: false,
+        record: false,
+        timing: false,
+        repeats: 1,
+        config,
+    };
+
+    let day = implementations::find_day(2).unwrap();
+    let rows = runner::collect_rows(&[day], None, &options);
+    assert!(rows.iter().all(|row| row.expected.is_none() && row.passed()));
+}